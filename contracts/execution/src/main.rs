@@ -28,13 +28,17 @@ use types::{
 
 use logic::{
     custom_types::custom_types::{ProjectSerialized, ProposalSerialized},
-    Project, Proposal, ProposalError, ProposalType, Voting, VotingEngineError,
+    OperationGroup, Project, Proposal, ProposalError, ProposalType, Role, Voting,
+    VotingEngineError,
 };
 
 const VOTING_CONTRACT_HASH_KEY: &str = "voting_contract_hash";
 const VOTING_ENGINE_CONTRACT_ADDRESS_KEY: &str = "voting_engine_contract_address";
 const NUMBER_OF_PROJECTS_KEY: &str = "projects_number";
 const GOVERNANCE_CONTRACT_HASH_KEY: &str = "governance_contract_hash";
+const DEPLOYER_ADDRESS_KEY: &str = "deployer_address";
+// Proves the execution engine role of this contract to the other DAO contracts
+const ENGINE_KEY: &str = "engine_key";
 
 #[casperlabs_contract]
 
@@ -45,6 +49,27 @@ mod ProjectExecutionEngine {
         set_key(VOTING_ENGINE_CONTRACT_ADDRESS_KEY, voting_engine_address);
         set_key(VOTING_CONTRACT_HASH_KEY, voting_contract_hash);
         set_key(NUMBER_OF_PROJECTS_KEY, 0);
+        set_key(DEPLOYER_ADDRESS_KEY, runtime::get_caller());
+        runtime::put_key(ENGINE_KEY, storage::new_uref(()).into());
+    }
+
+    // Bootstrap: the governance contract is deployed after the execution engine, the deployer
+    // sets it once and registers the engine key with it
+    #[casperlabs_method]
+    fn register_engine(governance_contract_hash: ContractHash) {
+        assert_caller(get_key(DEPLOYER_ADDRESS_KEY));
+        if (runtime::has_key(GOVERNANCE_CONTRACT_HASH_KEY)) {
+            runtime::revert(Error::EngineAlreadyRegistered);
+        }
+        set_key(GOVERNANCE_CONTRACT_HASH_KEY, governance_contract_hash);
+        let mut register_engine_args: RuntimeArgs = RuntimeArgs::new();
+        register_engine_args.insert("role", Role::ExecutionEngine as u8);
+        register_engine_args.insert("engine_key", engine_key());
+        runtime::call_contract::<()>(
+            governance_contract_hash,
+            "register_engine",
+            register_engine_args,
+        );
     }
 
     fn internal_get_voting_engine_address() -> AccountHash {
@@ -76,8 +101,9 @@ mod ProjectExecutionEngine {
     }
 
     #[casperlabs_method]
-    fn new_project(proposal: ProposalSerialized) -> bool {
-        assert_caller(internal_get_voting_engine_address());
+    fn new_project(proposal: ProposalSerialized, engine_key: URef) -> bool {
+        assert_not_paused(OperationGroup::Milestones);
+        assert_engine_role(Role::VotingEngine, engine_key);
         let index: U256 = get_key(NUMBER_OF_PROJECTS_KEY);
         Project::new(proposal)
             .map(|project| save_project(index, project))
@@ -90,6 +116,7 @@ mod ProjectExecutionEngine {
     // Mark milesetone as complete
     #[casperlabs_method]
     fn trigger_milestone_completion(project_index: U256) {
+        assert_not_paused(OperationGroup::Milestones);
         let project_serialized: ProjectSerialized = get_key(&project_key(project_index));
         let mut project: Project = Project::deserialize(project_serialized);
         assert_caller(project.proposal.proposer);
@@ -106,15 +133,17 @@ mod ProjectExecutionEngine {
         is_favorable: bool,
         recommendations: Vec<(String, String)>,
     ) {
+        assert_not_paused(OperationGroup::Milestones);
         let project_serialized: ProjectSerialized = get_key(&project_key(project_index));
         let mut project: Project = Project::deserialize(project_serialized);
-        assert_caller(project.proposal.proposer);
+        assert_role(Role::Reviewer);
         let mut args: RuntimeArgs = RuntimeArgs::new();
         let mut new_proposal: Proposal = project.clone().proposal;
         new_proposal.proposal_type = ProposalType::AnalysisAcceptance;
         // cost here is project ID
         new_proposal.cost = project_index;
         args.insert("proposal", new_proposal.serialize());
+        args.insert("engine_key", engine_key());
         let vote_index: U256 =
             runtime::call_contract(internal_get_voting_engine_contract_hash(), "new_vote", args);
         project
@@ -124,10 +153,15 @@ mod ProjectExecutionEngine {
         save_project(project_index, project);
     }
     #[casperlabs_method]
-    fn approve_milestone_analysis(project_index: U256, milestone_analysis_index: U256) {
+    fn approve_milestone_analysis(
+        project_index: U256,
+        milestone_analysis_index: U256,
+        engine_key: URef,
+    ) {
+        assert_not_paused(OperationGroup::Milestones);
         let project_serialized: ProjectSerialized = get_key(&project_key(project_index));
         let mut project: Project = Project::deserialize(project_serialized);
-        assert_caller(internal_get_voting_engine_address());
+        assert_engine_role(Role::VotingEngine, engine_key);
         let output: Option<BTreeMap<String, String>> = project
             .approve_milestone_analysis(milestone_analysis_index)
             .map_err(|e| runtime::revert(Error::from(e)))
@@ -146,6 +180,7 @@ mod ProjectExecutionEngine {
         let mut new_proposal: Proposal = project.proposal;
         new_proposal.proposal_type = ProposalType::AnalysisAcceptance;
         args.insert("proposal", new_proposal.serialize());
+        args.insert("engine_key", engine_key());
         runtime::call_contract::<U256>(
             internal_get_voting_engine_contract_hash(),
             "new_vote",
@@ -155,6 +190,7 @@ mod ProjectExecutionEngine {
 
     #[casperlabs_method]
     fn check_timeout(project_index: U256) -> bool {
+        assert_not_paused(OperationGroup::Milestones);
         // IF Milestone Expires without an OP Claim the Remediation Process begins;
         let project_serialized: ProjectSerialized = get_key(&project_key(project_index));
         let mut project: Project = Project::deserialize(project_serialized);
@@ -171,6 +207,19 @@ mod ProjectExecutionEngine {
     }
 }
 
+fn assert_not_paused(operation_group: OperationGroup) {
+    let mut is_paused_args: RuntimeArgs = RuntimeArgs::new();
+    is_paused_args.insert("operation_group", operation_group as u8);
+    let is_paused: bool = runtime::call_contract(
+        get_key(GOVERNANCE_CONTRACT_HASH_KEY),
+        "is_paused",
+        is_paused_args,
+    );
+    if (is_paused) {
+        runtime::revert(Error::OperationPaused);
+    }
+}
+
 fn save_project(project_index: U256, project: Project) {
     set_key(&project_key(project_index), project.serialize());
 }
@@ -189,17 +238,61 @@ pub enum Error {
     ProjectCostNotEqualToMilestonesSum,
     StakedRepGreaterThanReputationBalance,
     InvalidVotingContractAddress,
+    QuorumBelowPolicy,
+    ThresholdBelowPolicy,
+    DurationOutsidePolicy,
+    UnknownGovernanceAction,
+    GovernanceValueOutOfRange,
+    InvalidNumberOfGovernanceActions,
+    OperationPaused,
+    MissingRole,
+    EngineAlreadyRegistered,
+    NoVotePolicy,
+    StakingLimitsBelowPolicy,
+    CountingRuleNotAllowed,
+    SecretBallotOutsidePolicy,
+    StakingLimitsAbovePolicy,
 }
 
-pub fn assert_admin() {
-    let failsafe: AccountHash = get_key("_failSafe");
-    let compliance: AccountHash = get_key("_compliance");
-    let caller = runtime::get_caller();
-    if failsafe != caller || compliance != caller {
-        runtime::revert(Error::NotTheAdminAccount);
+fn has_role(role: Role, account: AccountHash) -> bool {
+    let mut has_role_args: RuntimeArgs = RuntimeArgs::new();
+    has_role_args.insert("role", role as u8);
+    has_role_args.insert("account", account);
+    runtime::call_contract(
+        get_key(GOVERNANCE_CONTRACT_HASH_KEY),
+        "has_role",
+        has_role_args,
+    )
+}
+
+fn assert_role(role: Role) {
+    if (!has_role(role, runtime::get_caller())) {
+        runtime::revert(Error::MissingRole);
+    }
+}
+
+fn assert_engine_role(role: Role, engine_key: URef) {
+    let mut has_engine_role_args: RuntimeArgs = RuntimeArgs::new();
+    has_engine_role_args.insert("role", role as u8);
+    has_engine_role_args.insert("engine_key", engine_key);
+    let has_engine_role: bool = runtime::call_contract(
+        get_key(GOVERNANCE_CONTRACT_HASH_KEY),
+        "has_engine_role",
+        has_engine_role_args,
+    );
+    if (!has_engine_role) {
+        runtime::revert(Error::MissingRole);
     }
 }
 
+fn engine_key() -> URef {
+    runtime::get_key(ENGINE_KEY)
+        .unwrap_or_revert()
+        .into_uref()
+        .unwrap_or_revert()
+        .into_read()
+}
+
 pub fn assert_caller(authorized_account: AccountHash) {
     let caller = runtime::get_caller();
     if caller != authorized_account {
@@ -231,6 +324,19 @@ impl From<ProposalError> for Error {
             ProposalError::StakedRepGreaterThanReputationBalance => {
                 Error::StakedRepGreaterThanReputationBalance
             }
+            ProposalError::QuorumBelowPolicy => Error::QuorumBelowPolicy,
+            ProposalError::ThresholdBelowPolicy => Error::ThresholdBelowPolicy,
+            ProposalError::DurationOutsidePolicy => Error::DurationOutsidePolicy,
+            ProposalError::UnknownGovernanceAction => Error::UnknownGovernanceAction,
+            ProposalError::GovernanceValueOutOfRange => Error::GovernanceValueOutOfRange,
+            ProposalError::InvalidNumberOfGovernanceActions => {
+                Error::InvalidNumberOfGovernanceActions
+            }
+            ProposalError::NoVotePolicy => Error::NoVotePolicy,
+            ProposalError::StakingLimitsBelowPolicy => Error::StakingLimitsBelowPolicy,
+            ProposalError::CountingRuleNotAllowed => Error::CountingRuleNotAllowed,
+            ProposalError::SecretBallotOutsidePolicy => Error::SecretBallotOutsidePolicy,
+            ProposalError::StakingLimitsAbovePolicy => Error::StakingLimitsAbovePolicy,
         }
     }
 }
@@ -18,7 +18,9 @@ pub enum Error {
     InvalidArgument2 = 25,             // 65561
     UnsupportedNumberOfArguments = 30, // 65566
     NotTheAdminAccount,
-    NotVotingEngine
+    MissingRole,
+    EngineAlreadyRegistered,
+    InvalidPause,
 }
 
 impl Error {
@@ -5,7 +5,7 @@
 
 extern crate alloc;
 
-use alloc::{collections::BTreeMap, collections::BTreeSet};
+use alloc::{collections::BTreeMap, collections::BTreeSet, vec::Vec};
 use core::convert::TryInto;
 
 use casperlabs_contract_macro::{casperlabs_constructor, casperlabs_contract, casperlabs_method};
@@ -25,12 +25,31 @@ use types::{
 };
 mod errors;
 use errors::Error;
+use logic::{
+    custom_types::custom_types::{
+        GovernanceActionSerialized, VotePoliciesSerialized, VotePolicySerialized,
+    },
+    plan_governance_actions, GovernanceAction, GovernanceActionResult, PauseState, ProposalType,
+    Role, RoleRegistry, VotePolicies, VotePolicy, NUMBER_OF_OPERATION_GROUPS, NUMBER_OF_ROLES,
+};
 const POLICING_RATIO_KEY: &str = "policing_ratio";
 const VOTING_CONTRACT_HASH_KEY: &str = "voting_contract_hash";
 const VOTING_ENGINE_CONTRACT_ADDRESS_KEY: &str = "voting_engine_contract_address";
 const REPUTATION_ALLOCATION_RATIO_KEY: &str = "reputation_allocation_ratio";
 const REPUTATION_CONTRACT_HASH_KEY: &str = "reputation_contract_hash";
 const EXECUTION_CONTRACT_HASH_KEY: &str = "execution_contract_hash";
+// (window, extension, max extensions), votes aren't extended while the window is 0
+const LATE_SWING_RULE_KEY: &str = "late_swing_rule";
+// Voters paid per page when votes are settled by the voting engine, votes are only settled
+// through claims while it is 0
+const SETTLEMENT_PAGE_SIZE_KEY: &str = "settlement_page_size";
+// (proposal type, lowest cost of the band) => vote policy, every proposal type has a band
+// from cost 0
+const VOTE_POLICIES_KEY: &str = "vote_policies";
+// Paused operation groups, checked by every DAO contract
+const PAUSE_STATE_KEY: &str = "pause_state";
+// Role => accounts holding it, checked by every DAO contract
+const ROLES_KEY: &str = "roles";
 
 #[casperlabs_contract]
 mod Governance {
@@ -42,7 +61,36 @@ mod Governance {
         reputation_contract_hash: ContractHash,
         policing_ratio: u64,
         execution_contract_hash: ContractHash,
+        failsafe: AccountHash,
+        compliance: AccountHash,
+        reviewers: Vec<AccountHash>,
+        default_vote_policy: VotePolicySerialized,
     ) {
+        // Engine roles are registered by the engines themselves once they are deployed
+        let mut roles: RoleRegistry = RoleRegistry::new();
+        roles.grant(Role::Failsafe, failsafe);
+        roles.grant(Role::Compliance, compliance);
+        for reviewer in reviewers {
+            roles.grant(Role::Reviewer, reviewer);
+        }
+        set_key(ROLES_KEY, roles.serialize());
+        // Proposals are rejected when no policy covers them, bands are refined by governance
+        let mut vote_policies: VotePolicies = VotePolicies::new();
+        for proposal_type in [
+            ProposalType::Grant,
+            ProposalType::Governance,
+            ProposalType::AnalysisAcceptance,
+            ProposalType::MultiOption,
+        ]
+        .iter()
+        {
+            vote_policies.set(
+                *proposal_type,
+                U256::from(0),
+                VotePolicy::deserialize(default_vote_policy),
+            );
+        }
+        set_key(VOTE_POLICIES_KEY, vote_policies.serialize());
         set_key(VOTING_ENGINE_CONTRACT_ADDRESS_KEY, voting_engine_address);
         set_key(POLICING_RATIO_KEY, policing_ratio);
         set_key(VOTING_CONTRACT_HASH_KEY, voting_contract_hash);
@@ -51,84 +99,217 @@ mod Governance {
     }
 
     #[casperlabs_method]
-    fn reputation_contract_hash() -> u64 {
+    fn reputation_contract_hash() -> ContractHash {
         get_key(REPUTATION_CONTRACT_HASH_KEY)
     }
     #[casperlabs_method]
-    fn update_reputation_contract_hash(update_reputation_contract_hash: AccountHash) -> bool {
-        assert_voting_engine();
-        set_key(
-            REPUTATION_CONTRACT_HASH_KEY,
-            update_reputation_contract_hash,
-        );
-        true
-    }
-    #[casperlabs_method]
     fn policing_ratio() -> u64 {
         get_key(POLICING_RATIO_KEY)
     }
     
-    #[casperlabs_method]
-    fn update_policing_ratio(update_policing_ratio: u64) -> bool {
-        assert_voting_engine();
-        set_key(POLICING_RATIO_KEY, update_policing_ratio);
-        true
-    }
     #[casperlabs_method]
     fn reputation_allocation_ratio() -> u64 {
         get_key(REPUTATION_ALLOCATION_RATIO_KEY)
     }
+
     #[casperlabs_method]
-    fn update_reputation_allocation_ratio(update_reputation_allocation_ratio: u64) -> bool {
-        assert_voting_engine();
-        set_key(
-            REPUTATION_ALLOCATION_RATIO_KEY,
-            update_reputation_allocation_ratio,
-        );
-        true
+    fn late_swing_rule() -> (u64, u64, u64) {
+        get_key(LATE_SWING_RULE_KEY)
     }
 
     #[casperlabs_method]
-    fn voting_engine_address() -> AccountHash {
-        get_key(VOTING_ENGINE_CONTRACT_ADDRESS_KEY)
+    fn settlement_page_size() -> u64 {
+        get_key(SETTLEMENT_PAGE_SIZE_KEY)
     }
+
     #[casperlabs_method]
-    fn update_voting_engine_address(update_voting_engine_address: AccountHash) -> bool {
-        assert_voting_engine();
-        set_key(
-            VOTING_ENGINE_CONTRACT_ADDRESS_KEY,
-            update_voting_engine_address,
-        );
-        true
+    fn vote_policies() -> VotePoliciesSerialized {
+        get_key(VOTE_POLICIES_KEY)
     }
 
     #[casperlabs_method]
-    fn voting_engine_contract_hash() -> ContractHash {
-        get_key(VOTING_CONTRACT_HASH_KEY)
+    fn voting_engine_address() -> AccountHash {
+        get_key(VOTING_ENGINE_CONTRACT_ADDRESS_KEY)
     }
+
     #[casperlabs_method]
-    fn update_voting_engine_contract_hash(
-        update_voting_engine_contract_hash: ContractHash,
-    ) -> bool {
-        set_key(VOTING_CONTRACT_HASH_KEY, update_voting_engine_contract_hash);
-        true
+    fn voting_engine_contract_hash() -> ContractHash {
+        get_key(VOTING_CONTRACT_HASH_KEY)
     }
     #[casperlabs_method]
     fn execution_contract_hash() -> ContractHash {
         get_key(EXECUTION_CONTRACT_HASH_KEY)
     }
+
+    #[casperlabs_method]
+    fn pause_state() -> BTreeMap<u8, u64> {
+        get_key(PAUSE_STATE_KEY)
+    }
+    #[casperlabs_method]
+    fn is_paused(operation_group: u8) -> bool {
+        let current_time: u64 = runtime::get_blocktime().into();
+        PauseState::deserialize(get_key(PAUSE_STATE_KEY))
+            .is_paused(operation_group.into(), current_time)
+    }
+    #[casperlabs_method]
+    fn has_role(role: u8, account: AccountHash) -> bool {
+        if (role >= NUMBER_OF_ROLES || Role::from(role).is_engine()) {
+            return false;
+        }
+        RoleRegistry::deserialize(get_key(ROLES_KEY)).has_role(role.into(), &account)
+    }
+    // A DAO contract proves an engine role with its engine key, a URef only the contract holds
+    // since the runtime rejects forged URefs in the arguments of a call
+    #[casperlabs_method]
+    fn has_engine_role(role: u8, engine_key: URef) -> bool {
+        if (role >= NUMBER_OF_ROLES || !Role::from(role).is_engine()) {
+            return false;
+        }
+        RoleRegistry::deserialize(get_key(ROLES_KEY))
+            .has_role(role.into(), &AccountHash::new(engine_key.addr()))
+    }
+    // Bootstrap: each engine registers its key once, relayed by the failsafe account. Engines
+    // are replaced later through GrantRole and RevokeRole governance actions
+    #[casperlabs_method]
+    fn register_engine(role: u8, engine_key: URef) {
+        assert_role(Role::Failsafe);
+        if (role >= NUMBER_OF_ROLES || !Role::from(role).is_engine()) {
+            runtime::revert(Error::MissingRole);
+        }
+        let mut roles: RoleRegistry = RoleRegistry::deserialize(get_key(ROLES_KEY));
+        if (!roles.role_members(role.into()).is_empty()) {
+            runtime::revert(Error::EngineAlreadyRegistered);
+        }
+        roles.grant(role.into(), AccountHash::new(engine_key.addr()));
+        set_key(ROLES_KEY, roles.serialize());
+    }
     #[casperlabs_method]
-    fn update_execution_contract_hash(update_execution_contract_hash: ContractHash) -> bool {
-        set_key(EXECUTION_CONTRACT_HASH_KEY, update_execution_contract_hash);
-        true
+    fn role_members(role: u8) -> Vec<AccountHash> {
+        if (role >= NUMBER_OF_ROLES) {
+            return Vec::new();
+        }
+        RoleRegistry::deserialize(get_key(ROLES_KEY)).role_members(role.into())
+    }
+    // Roles are only granted and revoked through governance actions, a holder can give up
+    // its own role
+    #[casperlabs_method]
+    fn renounce_role(role: u8) {
+        let mut roles: RoleRegistry = RoleRegistry::deserialize(get_key(ROLES_KEY));
+        if (role >= NUMBER_OF_ROLES || !roles.revoke(role.into(), &runtime::get_caller())) {
+            runtime::revert(Error::MissingRole);
+        }
+        set_key(ROLES_KEY, roles.serialize());
+    }
+
+    // Emergency stop: the failsafe account can pause operation groups for a limited time, only
+    // governance or the expiry lifts the pause
+    #[casperlabs_method]
+    fn pause_operations(operation_groups: Vec<u8>, duration: u64) {
+        assert_role(Role::Failsafe);
+        if (!PauseState::is_valid_duration(duration)
+            || operation_groups
+                .iter()
+                .any(|operation_group| *operation_group >= NUMBER_OF_OPERATION_GROUPS))
+        {
+            runtime::revert(Error::InvalidPause);
+        }
+        let current_time: u64 = runtime::get_blocktime().into();
+        let mut pause_state: PauseState = PauseState::deserialize(get_key(PAUSE_STATE_KEY));
+        for operation_group in operation_groups {
+            pause_state.pause(operation_group.into(), current_time, duration);
+        }
+        set_key(PAUSE_STATE_KEY, pause_state.serialize());
+    }
+
+    // Applies the actions of an approved governance proposal in order, all of them or none
+    // when one is invalid, the only way to change the parameters above. Returns the result
+    // of each action
+    #[casperlabs_method]
+    fn apply_governance_actions(
+        governance_actions: Vec<GovernanceActionSerialized>,
+        engine_key: URef,
+    ) -> Vec<u8> {
+        assert_engine_role(Role::VotingEngine, engine_key);
+        let (governance_actions, action_results): (
+            Vec<GovernanceAction>,
+            Vec<GovernanceActionResult>,
+        ) = plan_governance_actions(governance_actions);
+        for governance_action in governance_actions {
+            apply_governance_action(governance_action);
+        }
+        action_results
+            .into_iter()
+            .map(|action_result| action_result as u8)
+            .collect()
+    }
+}
+
+fn apply_governance_action(governance_action: GovernanceAction) {
+    match governance_action {
+        GovernanceAction::PolicingRatio(policing_ratio) => {
+            set_key(POLICING_RATIO_KEY, policing_ratio)
+        }
+        GovernanceAction::ReputationAllocationRatio(reputation_allocation_ratio) => {
+            set_key(REPUTATION_ALLOCATION_RATIO_KEY, reputation_allocation_ratio)
+        }
+        GovernanceAction::SettlementPageSize(settlement_page_size) => {
+            set_key(SETTLEMENT_PAGE_SIZE_KEY, settlement_page_size)
+        }
+        GovernanceAction::LateSwingRule(window, extension, max_extensions) => {
+            set_key(LATE_SWING_RULE_KEY, (window, extension, max_extensions))
+        }
+        GovernanceAction::VotePolicy(proposal_type, min_cost, vote_policy) => {
+            let mut vote_policies: VotePoliciesSerialized = get_key(VOTE_POLICIES_KEY);
+            vote_policies.insert((proposal_type as u8, min_cost), vote_policy.serialize());
+            set_key(VOTE_POLICIES_KEY, vote_policies);
+        }
+        GovernanceAction::VotingEngineAddress(voting_engine_address) => {
+            set_key(VOTING_ENGINE_CONTRACT_ADDRESS_KEY, voting_engine_address)
+        }
+        GovernanceAction::VotingEngineContractHash(voting_contract_hash) => {
+            set_key(VOTING_CONTRACT_HASH_KEY, voting_contract_hash)
+        }
+        GovernanceAction::ReputationContractHash(reputation_contract_hash) => {
+            set_key(REPUTATION_CONTRACT_HASH_KEY, reputation_contract_hash)
+        }
+        GovernanceAction::ExecutionContractHash(execution_contract_hash) => {
+            set_key(EXECUTION_CONTRACT_HASH_KEY, execution_contract_hash)
+        }
+        GovernanceAction::Pause(operation_group, duration) => {
+            let current_time: u64 = runtime::get_blocktime().into();
+            let mut pause_state: PauseState = PauseState::deserialize(get_key(PAUSE_STATE_KEY));
+            pause_state.pause(operation_group, current_time, duration);
+            set_key(PAUSE_STATE_KEY, pause_state.serialize());
+        }
+        GovernanceAction::Unpause(operation_group) => {
+            let mut pause_state: PauseState = PauseState::deserialize(get_key(PAUSE_STATE_KEY));
+            pause_state.unpause(operation_group);
+            set_key(PAUSE_STATE_KEY, pause_state.serialize());
+        }
+        GovernanceAction::GrantRole(role, account) => {
+            let mut roles: RoleRegistry = RoleRegistry::deserialize(get_key(ROLES_KEY));
+            roles.grant(role, account);
+            set_key(ROLES_KEY, roles.serialize());
+        }
+        GovernanceAction::RevokeRole(role, account) => {
+            let mut roles: RoleRegistry = RoleRegistry::deserialize(get_key(ROLES_KEY));
+            roles.revoke(role, &account);
+            set_key(ROLES_KEY, roles.serialize());
+        }
+    }
+}
+
+fn assert_role(role: Role) {
+    let roles: RoleRegistry = RoleRegistry::deserialize(get_key(ROLES_KEY));
+    if (!roles.has_role(role, &runtime::get_caller())) {
+        runtime::revert(Error::MissingRole);
     }
 }
 
-pub fn assert_voting_engine() {
-    let voting_engine_address: AccountHash = get_key(VOTING_ENGINE_CONTRACT_ADDRESS_KEY);
-    let caller = runtime::get_caller();
-    if voting_engine_address != caller {
-        runtime::revert(Error::NotVotingEngine);
+fn assert_engine_role(role: Role, engine_key: URef) {
+    let roles: RoleRegistry = RoleRegistry::deserialize(get_key(ROLES_KEY));
+    if (!roles.has_role(role, &AccountHash::new(engine_key.addr()))) {
+        runtime::revert(Error::MissingRole);
     }
 }
 
@@ -27,20 +27,23 @@ use types::{
 };
 
 use logic::{
-    custom_types::custom_types::GovernanceVoteConfigurationSerialized, GovernanceProposal,
-    Proposal, ProposalError, Voting, VotingEngineError,
+    custom_types::custom_types::{
+        GovernanceActionSerialized, GovernanceVoteConfigurationSerialized,
+        VoteConfigurationSerialized, VotePoliciesSerialized,
+    },
+    GovernanceProposal, OperationGroup, Proposal, ProposalError, Role, VotePolicies, Voting,
+    VotingEngineError,
 };
 
 const MINIMUM_STABILITY_TIME_KEY: &str = "minimum_stability_time";
 const REPUTATION_CONTRACT_HASH_KEY: &str = "reputation_contract_hash";
 const GOVERNANCE_ADDRESS_KEY: &str = "governance";
-const VOTING_CONTRACT_HASH_KEY: &str = "voting_contract_hash";
-const VOTING_CONTRACT_ADDRESS_KEY: &str = "voting_contract_address";
 const DEPLOYER_ADDRESS_KEY: &str = "deployer_address";
-const VOTING_CONTRACT_CHANGED_KEY: &str = "voting_contract_changed";
 const NUMBER_OF_GOVERNANCE_PROPOSALS_KEY: &str = "governance_proposals_number";
 const NUMBER_OF_PROPOSALS_KEY: &str = "proposals_number";
 const GOVERNANCE_CONTRACT_HASH_KEY: &str = "governance_contract_hash";
+// Proves the proposal engine role of this contract to the other DAO contracts
+const ENGINE_KEY: &str = "engine_key";
 #[casperlabs_contract]
 
 mod ProposalEngine {
@@ -54,8 +57,22 @@ mod ProposalEngine {
         set_key(MINIMUM_STABILITY_TIME_KEY, minimum_stability_time);
         set_key(GOVERNANCE_ADDRESS_KEY, governance_address);
         set_key(DEPLOYER_ADDRESS_KEY, runtime::get_caller());
-        set_key(VOTING_CONTRACT_CHANGED_KEY, false);
         set_key(GOVERNANCE_CONTRACT_HASH_KEY, governance_contract_hash);
+        runtime::put_key(ENGINE_KEY, storage::new_uref(()).into());
+    }
+
+    // Bootstrap: the deployer registers the engine key with the governance contract once
+    #[casperlabs_method]
+    fn register_engine() {
+        assert_caller(get_key(DEPLOYER_ADDRESS_KEY));
+        let mut register_engine_args: RuntimeArgs = RuntimeArgs::new();
+        register_engine_args.insert("role", Role::ProposalEngine as u8);
+        register_engine_args.insert("engine_key", engine_key());
+        runtime::call_contract::<()>(
+            get_key(GOVERNANCE_CONTRACT_HASH_KEY),
+            "register_engine",
+            register_engine_args,
+        );
     }
 
     #[casperlabs_method]
@@ -97,6 +114,30 @@ mod ProposalEngine {
             args,
         )
     }
+    fn internal_get_voting_engine_address() -> AccountHash {
+        let args: RuntimeArgs = RuntimeArgs::new();
+        runtime::call_contract::<AccountHash>(
+            get_key(GOVERNANCE_CONTRACT_HASH_KEY),
+            "voting_engine_address",
+            args,
+        )
+    }
+    fn internal_get_voting_engine_contract_hash() -> ContractHash {
+        let args: RuntimeArgs = RuntimeArgs::new();
+        runtime::call_contract::<ContractHash>(
+            get_key(GOVERNANCE_CONTRACT_HASH_KEY),
+            "voting_engine_contract_hash",
+            args,
+        )
+    }
+    fn internal_get_vote_policies() -> VotePolicies {
+        let args: RuntimeArgs = RuntimeArgs::new();
+        VotePolicies::deserialize(runtime::call_contract::<VotePoliciesSerialized>(
+            get_key(GOVERNANCE_CONTRACT_HASH_KEY),
+            "vote_policies",
+            args,
+        ))
+    }
 
     #[casperlabs_method]
     fn create_proposal(
@@ -106,18 +147,15 @@ mod ProposalEngine {
         category: u8,
         citations: Vec<u64>,
         ratios: (u64, u64, u64),
-        vote_configuration: ((u64, U256), (u8, u64, U256)),
+        vote_configuration: VoteConfigurationSerialized,
         milestones: Vec<((u8, u8, Vec<(u8, U256, U256)>), u64)>,
         staked_rep: U256,
         sponsors: Vec<(AccountHash, U256)>,
         cost: U256,
     ) {
+        assert_not_paused(OperationGroup::Proposals);
         let caller = runtime::get_caller();
         let reputation_contract_hash: ContractHash = internal_get_reputation_contract_hash();
-        let voting_contract_changed: bool = get_key(VOTING_CONTRACT_CHANGED_KEY);
-        if !voting_contract_changed {
-            runtime::revert(Error::InvalidVotingContractAddress);
-        }
         let mut is_member_args: RuntimeArgs = RuntimeArgs::new();
         is_member_args.insert("account", runtime::get_caller());
 
@@ -130,9 +168,10 @@ mod ProposalEngine {
         let mut balance_of_args: RuntimeArgs = RuntimeArgs::new();
         balance_of_args.insert("account", runtime::get_caller());
         let reputation_balance: U256 =
-            runtime::call_contract::<U256>(reputation_contract_hash, "balanceOf", balance_of_args);
+            runtime::call_contract::<U256>(reputation_contract_hash, "balance_of", balance_of_args);
 
         let system_policing_ratio: u64 = internal_get_policing_ratio();
+        let current_time: u64 = runtime::get_blocktime().into();
 
         let proposal: Proposal = Proposal::new(
             name,
@@ -149,6 +188,8 @@ mod ProposalEngine {
             reputation_balance,
             sponsors,
             cost,
+            internal_get_vote_policies().policy_for(logic::ProposalType::Grant, cost),
+            current_time,
         )
         .map_err(|e| runtime::revert(Error::from(e)))
         .unwrap();
@@ -157,18 +198,22 @@ mod ProposalEngine {
         save_proposal(new_proposal_index, proposal.clone());
 
         // Send staked rep to voting engine
-        let voting_contract_address: ContractHash = get_key(VOTING_CONTRACT_ADDRESS_KEY);
         let mut transfer_args: RuntimeArgs = RuntimeArgs::new();
         transfer_args.insert("from", runtime::get_caller());
-        transfer_args.insert("to", voting_contract_address);
+        transfer_args.insert("to", internal_get_voting_engine_address());
         transfer_args.insert("amount", staked_rep);
+        transfer_args.insert("engine_key", engine_key());
         // Create new vote in voting engine
         let reputation_contract_hash: ContractHash = internal_get_reputation_contract_hash();
         runtime::call_contract::<bool>(reputation_contract_hash, "transferFrom", transfer_args);
         let mut new_vote_args: RuntimeArgs = RuntimeArgs::new();
         new_vote_args.insert("proposal", Proposal::serialize(&proposal));
-        let voting_contract_hash: ContractHash = get_key(VOTING_CONTRACT_HASH_KEY);
-        runtime::call_contract::<U256>(voting_contract_hash, "new_vote", new_vote_args);
+        new_vote_args.insert("engine_key", engine_key());
+        runtime::call_contract::<U256>(
+            internal_get_voting_engine_contract_hash(),
+            "new_vote",
+            new_vote_args,
+        );
     }
 
     #[casperlabs_method]
@@ -178,14 +223,11 @@ mod ProposalEngine {
         staked_rep: U256,
         sponsors: Vec<(AccountHash, U256)>,
         repository_url: String,
-        new_variable_key_value: (String, String),
+        governance_actions: Vec<GovernanceActionSerialized>,
     ) {
+        assert_not_paused(OperationGroup::Proposals);
         let caller = runtime::get_caller();
         let reputation_contract_hash: ContractHash = internal_get_reputation_contract_hash();
-        let voting_contract_changed: bool = get_key(VOTING_CONTRACT_CHANGED_KEY);
-        if !voting_contract_changed {
-            runtime::revert(Error::InvalidVotingContractAddress);
-        }
         let mut is_member_args: RuntimeArgs = RuntimeArgs::new();
         is_member_args.insert("account", runtime::get_caller());
 
@@ -198,9 +240,10 @@ mod ProposalEngine {
         let mut balance_of_args: RuntimeArgs = RuntimeArgs::new();
         balance_of_args.insert("account", runtime::get_caller());
         let reputation_balance: U256 =
-            runtime::call_contract::<U256>(reputation_contract_hash, "balanceOf", balance_of_args);
+            runtime::call_contract::<U256>(reputation_contract_hash, "balance_of", balance_of_args);
 
         // TO DO: Use Oracles to get $100 Equivalent
+        let current_time: u64 = runtime::get_blocktime().into();
 
         let governance_proposal: GovernanceProposal = GovernanceProposal::new(
             name,
@@ -210,7 +253,9 @@ mod ProposalEngine {
             reputation_balance,
             sponsors,
             repository_url,
-            new_variable_key_value,
+            governance_actions,
+            internal_get_vote_policies().policy_for(logic::ProposalType::Governance, U256::from(0)),
+            current_time,
         )
         .map_err(|e| runtime::revert(Error::from(e)))
         .unwrap();
@@ -219,11 +264,11 @@ mod ProposalEngine {
         save_governance_proposal(new_governance_proposal_index, governance_proposal.clone());
 
         // Send staked rep to voting engine
-        let voting_contract_address: ContractHash = get_key(VOTING_CONTRACT_ADDRESS_KEY);
         let mut transfer_args: RuntimeArgs = RuntimeArgs::new();
         transfer_args.insert("from", runtime::get_caller());
-        transfer_args.insert("to", voting_contract_address);
+        transfer_args.insert("to", internal_get_voting_engine_address());
         transfer_args.insert("amount", staked_rep);
+        transfer_args.insert("engine_key", engine_key());
         // Create new vote in voting engine
         let reputation_contract_hash: ContractHash = internal_get_reputation_contract_hash();
         runtime::call_contract::<bool>(reputation_contract_hash, "transferFrom", transfer_args);
@@ -232,8 +277,28 @@ mod ProposalEngine {
             "proposal",
             GovernanceProposal::serialize(&governance_proposal),
         );
-        let voting_contract_hash: ContractHash = get_key(VOTING_CONTRACT_HASH_KEY);
-        runtime::call_contract::<bool>(voting_contract_hash, "new_governance_vote", new_vote_args);
+        // Approved governance actions wait the minimum stability time before they are executed
+        let minimum_stability_time: U256 = get_key(MINIMUM_STABILITY_TIME_KEY);
+        new_vote_args.insert("execution_delay", minimum_stability_time.as_u64());
+        new_vote_args.insert("engine_key", engine_key());
+        runtime::call_contract::<bool>(
+            internal_get_voting_engine_contract_hash(),
+            "new_governance_vote",
+            new_vote_args,
+        );
+    }
+}
+
+fn assert_not_paused(operation_group: OperationGroup) {
+    let mut is_paused_args: RuntimeArgs = RuntimeArgs::new();
+    is_paused_args.insert("operation_group", operation_group as u8);
+    let is_paused: bool = runtime::call_contract(
+        get_key(GOVERNANCE_CONTRACT_HASH_KEY),
+        "is_paused",
+        is_paused_args,
+    );
+    if (is_paused) {
+        runtime::revert(Error::OperationPaused);
     }
 }
 
@@ -273,6 +338,18 @@ pub enum Error {
     ProjectCostNotEqualToMilestonesSum,
     StakedRepGreaterThanReputationBalance,
     InvalidVotingContractAddress,
+    QuorumBelowPolicy,
+    ThresholdBelowPolicy,
+    DurationOutsidePolicy,
+    UnknownGovernanceAction,
+    GovernanceValueOutOfRange,
+    InvalidNumberOfGovernanceActions,
+    OperationPaused,
+    NoVotePolicy,
+    StakingLimitsBelowPolicy,
+    CountingRuleNotAllowed,
+    SecretBallotOutsidePolicy,
+    StakingLimitsAbovePolicy,
 }
 #[repr(u16)]
 pub enum ProposalStatus {
@@ -292,13 +369,12 @@ pub enum ProposalType {
     External,
 }
 
-pub fn assert_admin() {
-    let failsafe: AccountHash = get_key("_failSafe");
-    let compliance: AccountHash = get_key("_compliance");
-    let caller = runtime::get_caller();
-    if failsafe != caller || compliance != caller {
-        runtime::revert(Error::NotTheAdminAccount);
-    }
+fn engine_key() -> URef {
+    runtime::get_key(ENGINE_KEY)
+        .unwrap_or_revert()
+        .into_uref()
+        .unwrap_or_revert()
+        .into_read()
 }
 
 pub fn assert_caller(authorized_account: AccountHash) {
@@ -332,6 +408,19 @@ impl From<ProposalError> for Error {
             ProposalError::StakedRepGreaterThanReputationBalance => {
                 Error::StakedRepGreaterThanReputationBalance
             }
+            ProposalError::QuorumBelowPolicy => Error::QuorumBelowPolicy,
+            ProposalError::ThresholdBelowPolicy => Error::ThresholdBelowPolicy,
+            ProposalError::DurationOutsidePolicy => Error::DurationOutsidePolicy,
+            ProposalError::UnknownGovernanceAction => Error::UnknownGovernanceAction,
+            ProposalError::GovernanceValueOutOfRange => Error::GovernanceValueOutOfRange,
+            ProposalError::InvalidNumberOfGovernanceActions => {
+                Error::InvalidNumberOfGovernanceActions
+            }
+            ProposalError::NoVotePolicy => Error::NoVotePolicy,
+            ProposalError::StakingLimitsBelowPolicy => Error::StakingLimitsBelowPolicy,
+            ProposalError::CountingRuleNotAllowed => Error::CountingRuleNotAllowed,
+            ProposalError::SecretBallotOutsidePolicy => Error::SecretBallotOutsidePolicy,
+            ProposalError::StakingLimitsAbovePolicy => Error::StakingLimitsAbovePolicy,
         }
     }
 }
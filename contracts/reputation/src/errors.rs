@@ -17,7 +17,10 @@ pub enum Error {
     InvalidArgument1 = 24,             // 65560
     InvalidArgument2 = 25,             // 65561
     UnsupportedNumberOfArguments = 30, // 65566
-    NotTheAdminAccount
+    NotTheAdminAccount,
+    GovernanceContractAlreadySet,
+    OperationPaused,
+    MissingRole,
 }
 
 impl Error {
@@ -22,12 +22,19 @@ use types::{
         UpdateKeyFailure, Weight,
     },
     bytesrepr::{FromBytes, ToBytes},
+    contracts::ContractHash,
     contracts::{EntryPoint, EntryPointAccess, EntryPointType, EntryPoints},
     runtime_args, ApiError, CLType, CLTyped, CLValue, Group, Parameter, RuntimeArgs, URef, U256,
 };
 mod errors;
 use errors::Error;
+use logic::{OperationGroup, Role};
 const CURRENT_SUPPLY_KEY: &str = "_currentSupply";
+const MEMBER_COUNT_KEY: &str = "_memberCount";
+const SUPPLY_CHECKPOINTS_KEY: &str = "_supplyCheckpoints";
+const MEMBER_COUNT_CHECKPOINTS_KEY: &str = "_memberCountCheckpoints";
+// Holds the pause state and the roles, set once after the governance contract is deployed
+const GOVERNANCE_CONTRACT_HASH_KEY: &str = "_governanceContractHash";
 #[casperlabs_contract]
 mod Reputation {
     use types::account::UpdateKeyFailure;
@@ -43,12 +50,14 @@ mod Reputation {
         set_key("_name", tokenName);
         set_key("_symbol", tokenSymbol);
         set_key("_granularity", 1);
-        set_key("_currentSupply", 0);
+        set_key(CURRENT_SUPPLY_KEY, U256::from(0));
+        write_checkpoint(SUPPLY_CHECKPOINTS_KEY, U256::from(0));
         set_key("_votingEngine", voting_engine);
         set_key("_failSafe", failsafe);
         set_key("_compliance", compliance);
-        set_key(&member_key(&compliance), true);
-        set_key(&member_key(&failsafe), true);
+        set_key(MEMBER_COUNT_KEY, 0 as u64);
+        _add_member(compliance);
+        _add_member(failsafe);
     }
 
     #[casperlabs_method]
@@ -75,8 +84,26 @@ mod Reputation {
     fn balance_of(account: AccountHash) -> U256 {
         get_key(&balance_key(&account))
     }
+
+    // Balances, supply and member count as they were at block time `timestamp`
+    #[casperlabs_method]
+    fn balance_of_at(account: AccountHash, timestamp: u64) -> U256 {
+        checkpoint_at(&balance_checkpoints_key(&account), timestamp)
+    }
+
+    #[casperlabs_method]
+    fn current_supply_at(timestamp: u64) -> U256 {
+        checkpoint_at(SUPPLY_CHECKPOINTS_KEY, timestamp)
+    }
+
+    #[casperlabs_method]
+    fn member_count_at(timestamp: u64) -> u64 {
+        checkpoint_at(MEMBER_COUNT_CHECKPOINTS_KEY, timestamp)
+    }
+
     #[casperlabs_method]
     fn transfer(recipient: AccountHash, amount: U256) -> bool {
+        assert_not_paused(OperationGroup::Transfers);
         _transfer(runtime::get_caller(), recipient, amount);
         true
     }
@@ -85,25 +112,26 @@ mod Reputation {
         let sender_key = balance_key(&sender);
         let recipient_key = balance_key(&recipient);
         let new_sender_balance: U256 = (get_key::<U256>(&sender_key) - amount);
-        set_key(&sender_key, new_sender_balance);
+        set_balance(&sender, new_sender_balance);
         let new_recipient_balance: U256 = (get_key::<U256>(&recipient_key) + amount);
-        set_key(&recipient_key, new_recipient_balance);
+        set_balance(&recipient, new_recipient_balance);
     }
 
     #[casperlabs_method]
-    fn transferFrom(from: AccountHash, to: AccountHash, amount: U256) -> bool {
-        assert_admin();
+    fn transferFrom(from: AccountHash, to: AccountHash, amount: U256, engine_key: URef) -> bool {
+        assert_not_paused(OperationGroup::Transfers);
+        assert_any_engine_role(&[Role::ProposalEngine, Role::VotingEngine], engine_key);
         let mut sender_balance: U256 = get_key(&balance_key(&from));
         let mut receiver_balance: U256 = get_key(&balance_key(&to));
         sender_balance = sender_balance - amount;
         receiver_balance = receiver_balance + amount;
-        set_key(&balance_key(&from), sender_balance);
-        set_key(&balance_key(&to), receiver_balance);
+        set_balance(&from, sender_balance);
+        set_balance(&to, receiver_balance);
         true
     }
     #[casperlabs_method]
     fn mint(account: AccountHash, weight: Weight) {
-        // assert_admin();
+        assert_role(Role::Compliance);
         // let mut currentSupply: U256 = get_key(CURRENT_SUPPLY_KEY);
         // currentSupply = currentSupply + amount;
         // set_key(&CURRENT_SUPPLY_KEY, currentSupply);
@@ -126,12 +154,27 @@ mod Reputation {
     }
     #[casperlabs_method]
     fn burn(account: AccountHash, amount: U256) {
-        assert_admin();
+        assert_not_paused(OperationGroup::Transfers);
+        assert_role(Role::Compliance);
         let mut currentSupply: U256 = get_key(CURRENT_SUPPLY_KEY);
         currentSupply = currentSupply - amount;
         set_key(&CURRENT_SUPPLY_KEY, currentSupply);
+        write_checkpoint(SUPPLY_CHECKPOINTS_KEY, currentSupply);
+        let new_balance: U256 = get_key(&balance_key(&account));
+        set_balance(&account, new_balance - amount);
+    }
+
+    // New reputation only enters through the voting engine, as the input of approved grants
+    #[casperlabs_method]
+    fn mint_reputation(account: AccountHash, amount: U256, engine_key: URef) {
+        assert_not_paused(OperationGroup::Transfers);
+        assert_any_engine_role(&[Role::VotingEngine], engine_key);
+        let mut currentSupply: U256 = get_key(CURRENT_SUPPLY_KEY);
+        currentSupply = currentSupply + amount;
+        set_key(&CURRENT_SUPPLY_KEY, currentSupply);
+        write_checkpoint(SUPPLY_CHECKPOINTS_KEY, currentSupply);
         let new_balance: U256 = get_key(&balance_key(&account));
-        set_key(&balance_key(&account), new_balance - amount);
+        set_balance(&account, new_balance + amount);
     }
 
     #[casperlabs_method]
@@ -139,24 +182,107 @@ mod Reputation {
         runtime::has_key(&member_key(&account))
     }
 
+    #[casperlabs_method]
+    fn set_governance_contract_hash(governance_contract_hash: ContractHash) {
+        let failsafe: AccountHash = get_key("_failSafe");
+        if failsafe != runtime::get_caller() {
+            runtime::revert(Error::NotTheAdminAccount);
+        }
+        if runtime::has_key(GOVERNANCE_CONTRACT_HASH_KEY) {
+            runtime::revert(Error::GovernanceContractAlreadySet);
+        }
+        set_key(GOVERNANCE_CONTRACT_HASH_KEY, governance_contract_hash);
+    }
+
+    #[casperlabs_method]
+    fn member_count() -> u64 {
+        get_key(MEMBER_COUNT_KEY)
+    }
+
     #[casperlabs_method]
     fn add_member(account: AccountHash) {
-        assert_admin();
+        assert_role(Role::Compliance);
+        _add_member(account);
+    }
+
+    fn _add_member(account: AccountHash) {
+        if runtime::has_key(&member_key(&account)) {
+            return;
+        }
         set_key(&member_key(&account), true);
+        let member_count: u64 = get_key(MEMBER_COUNT_KEY);
+        set_key(MEMBER_COUNT_KEY, member_count + 1);
+        write_checkpoint(MEMBER_COUNT_CHECKPOINTS_KEY, member_count + 1);
     }
+
     #[casperlabs_method]
     fn remove_member(account: AccountHash) {
-        assert_admin();
+        assert_role(Role::Compliance);
+        if !runtime::has_key(&member_key(&account)) {
+            return;
+        }
         runtime::remove_key(&member_key(&account));
+        let member_count: u64 = get_key(MEMBER_COUNT_KEY);
+        set_key(MEMBER_COUNT_KEY, member_count - 1);
+        write_checkpoint(MEMBER_COUNT_CHECKPOINTS_KEY, member_count - 1);
     }
 }
 
-pub fn assert_admin() {
-    let failsafe: AccountHash = get_key("_failSafe");
-    let compliance: AccountHash = get_key("_compliance");
-    let caller = runtime::get_caller();
-    if failsafe != caller || compliance != caller {
-        runtime::revert(Error::NotTheAdminAccount);
+fn assert_not_paused(operation_group: OperationGroup) {
+    // Nothing can be paused until the governance contract is known
+    if !runtime::has_key(GOVERNANCE_CONTRACT_HASH_KEY) {
+        return;
+    }
+    let mut is_paused_args: RuntimeArgs = RuntimeArgs::new();
+    is_paused_args.insert("operation_group", operation_group as u8);
+    let is_paused: bool = runtime::call_contract(
+        get_key(GOVERNANCE_CONTRACT_HASH_KEY),
+        "is_paused",
+        is_paused_args,
+    );
+    if is_paused {
+        runtime::revert(Error::OperationPaused);
+    }
+}
+
+fn has_role(role: Role, account: AccountHash) -> bool {
+    // No account holds a role until the governance contract is known
+    if !runtime::has_key(GOVERNANCE_CONTRACT_HASH_KEY) {
+        return false;
+    }
+    let mut has_role_args: RuntimeArgs = RuntimeArgs::new();
+    has_role_args.insert("role", role as u8);
+    has_role_args.insert("account", account);
+    runtime::call_contract(
+        get_key(GOVERNANCE_CONTRACT_HASH_KEY),
+        "has_role",
+        has_role_args,
+    )
+}
+
+fn assert_role(role: Role) {
+    if !has_role(role, runtime::get_caller()) {
+        runtime::revert(Error::MissingRole);
+    }
+}
+
+fn has_engine_role(role: Role, engine_key: URef) -> bool {
+    if !runtime::has_key(GOVERNANCE_CONTRACT_HASH_KEY) {
+        return false;
+    }
+    let mut has_engine_role_args: RuntimeArgs = RuntimeArgs::new();
+    has_engine_role_args.insert("role", role as u8);
+    has_engine_role_args.insert("engine_key", engine_key);
+    runtime::call_contract(
+        get_key(GOVERNANCE_CONTRACT_HASH_KEY),
+        "has_engine_role",
+        has_engine_role_args,
+    )
+}
+
+fn assert_any_engine_role(roles: &[Role], engine_key: URef) {
+    if !roles.iter().any(|role| has_engine_role(*role, engine_key)) {
+        runtime::revert(Error::MissingRole);
     }
 }
 
@@ -183,10 +309,75 @@ fn set_key<T: ToBytes + CLTyped>(name: &str, value: T) {
     }
 }
 
+fn set_balance(account: &AccountHash, balance: U256) {
+    set_key(&balance_key(account), balance);
+    write_checkpoint(&balance_checkpoints_key(account), balance);
+}
+
+// Checkpoints are (block time, value) pairs in increasing time order, each under its own
+// indexed key next to their count so a change only writes one of them. A value changed several
+// times in the same block keeps a single checkpoint
+fn write_checkpoint<T: ToBytes + FromBytes + CLTyped + Copy + Default>(name: &str, value: T) {
+    let current_time: u64 = runtime::get_blocktime().into();
+    let checkpoint_count: u64 = get_key(&checkpoint_count_key(name));
+    if checkpoint_count > 0 {
+        let last_checkpoint: (u64, T) = get_key(&checkpoint_key(name, checkpoint_count - 1));
+        if last_checkpoint.0 == current_time {
+            set_key(
+                &checkpoint_key(name, checkpoint_count - 1),
+                (current_time, value),
+            );
+            return;
+        }
+    }
+    set_key(
+        &checkpoint_key(name, checkpoint_count),
+        (current_time, value),
+    );
+    set_key(&checkpoint_count_key(name), checkpoint_count + 1);
+}
+
+// Value of the latest checkpoint at or before `timestamp`, the default when there is none.
+// Binary search over the indexed keys, reading one checkpoint per step
+fn checkpoint_at<T: ToBytes + FromBytes + CLTyped + Copy + Default>(
+    name: &str,
+    timestamp: u64,
+) -> T {
+    // Checkpoints below `low` are at or before `timestamp`, those from `high` are after it
+    let mut low: u64 = 0;
+    let mut high: u64 = get_key(&checkpoint_count_key(name));
+    while low < high {
+        let middle: u64 = low + (high - low) / 2;
+        let checkpoint: (u64, T) = get_key(&checkpoint_key(name, middle));
+        if checkpoint.0 <= timestamp {
+            low = middle + 1;
+        } else {
+            high = middle;
+        }
+    }
+    if low == 0 {
+        return Default::default();
+    }
+    let checkpoint: (u64, T) = get_key(&checkpoint_key(name, low - 1));
+    checkpoint.1
+}
+
+fn checkpoint_key(name: &str, index: u64) -> String {
+    format!("{}_{}", name, index)
+}
+
+fn checkpoint_count_key(name: &str) -> String {
+    format!("{}_count", name)
+}
+
 fn balance_key(account: &AccountHash) -> String {
     format!("_balances_{}", account)
 }
 
+fn balance_checkpoints_key(account: &AccountHash) -> String {
+    format!("_balanceCheckpoints_{}", account)
+}
+
 fn member_key(account: &AccountHash) -> String {
     format!("_members_{}", account)
 }
@@ -34,6 +34,29 @@ pub enum Error {
     InvalidReputationToStake,
     StakingLimitReached,
     AlreadyVoted,
+    VoteIsSecret,
+    VoteIsNotSecret,
+    RevealNotOngoing,
+    NoCommitment,
+    AlreadyRevealed,
+    InvalidReveal,
+    CannotDelegateToSelf,
+    NotDelegated,
+    NotVoted,
+    InvalidOptions,
+    InvalidChoices,
+    TotalStakeLimitReached,
+    NoReputationAtSnapshot,
+    PushSettlementDisabled,
+    ExecutionNotQueued,
+    TimelockNotExpired,
+    VetoWindowClosed,
+    MissingRole,
+    EngineAlreadyRegistered,
+    OperationPaused,
+    NoVotePolicy,
+    OutsideVotePolicy,
+    OutcomeLocked,
 }
 
 impl Error {
@@ -77,6 +100,25 @@ impl From<VotingEngineError> for Error {
             VotingEngineError::InvalidReputationToStake => Error::InvalidReputationToStake,
             VotingEngineError::StakingLimitReached => Error::StakingLimitReached,
             VotingEngineError::AlreadyVoted => Error::AlreadyVoted,
+            VotingEngineError::VoteIsSecret => Error::VoteIsSecret,
+            VotingEngineError::VoteIsNotSecret => Error::VoteIsNotSecret,
+            VotingEngineError::RevealNotOngoing => Error::RevealNotOngoing,
+            VotingEngineError::NoCommitment => Error::NoCommitment,
+            VotingEngineError::AlreadyRevealed => Error::AlreadyRevealed,
+            VotingEngineError::InvalidReveal => Error::InvalidReveal,
+            VotingEngineError::CannotDelegateToSelf => Error::CannotDelegateToSelf,
+            VotingEngineError::NotDelegated => Error::NotDelegated,
+            VotingEngineError::NotVoted => Error::NotVoted,
+            VotingEngineError::InvalidOptions => Error::InvalidOptions,
+            VotingEngineError::InvalidChoices => Error::InvalidChoices,
+            VotingEngineError::TotalStakeLimitReached => Error::TotalStakeLimitReached,
+            VotingEngineError::NoReputationAtSnapshot => Error::NoReputationAtSnapshot,
+            VotingEngineError::ExecutionNotQueued => Error::ExecutionNotQueued,
+            VotingEngineError::TimelockNotExpired => Error::TimelockNotExpired,
+            VotingEngineError::VetoWindowClosed => Error::VetoWindowClosed,
+            VotingEngineError::NoVotePolicy => Error::NoVotePolicy,
+            VotingEngineError::OutsideVotePolicy => Error::OutsideVotePolicy,
+            VotingEngineError::OutcomeLocked => Error::OutcomeLocked,
         }
     }
 }
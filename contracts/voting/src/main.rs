@@ -30,9 +30,14 @@ mod errors;
 use errors::Error;
 use logic::{
     custom_types::custom_types::{
-        GovernanceProposalSerialized, ProposalSerialized, VotersSerialized, VotingSerialized,
+        DelegationSerialized, GovernanceActionSerialized, GovernanceExecutionSerialized,
+        GovernanceProposalSerialized, LateSwingRuleSerialized, MultiOptionVotingSerialized,
+        ProposalSerialized, ReputationLocksSerialized, VoteOutcomeReportSerialized,
+        VotePoliciesSerialized, VotersSerialized, VotingSerialized,
     },
-    Proposal, ProposalType, VoteResult, Voting,
+    simulate_vote, Delegation, GovernanceAction, GovernanceActionResult, LateSwingRule,
+    MultiOptionMethod, MultiOptionVoting, OperationGroup, Proposal, ProposalType, ReputationLocks,
+    Role, VoteDirection, VotePolicies, VoteResult, VoteType, Voting, VotingEngineError,
 };
 
 const GOVERNANCE_CONTRACT_HASH_KEY: &str = "governance_contract_hash";
@@ -40,38 +45,86 @@ const GOVERNANCE_CONTRACT_HASH_KEY: &str = "governance_contract_hash";
 // const REPUTATION_CONTRACT_ADDRESS_KEY: &str = "reputation_contract_address";
 // const REPUTATION_ALLOCATION_RATIO_KEY: &str = "reputation_allocation_ratio";
 const NUMBER_OF_VOTES_KEY: &str = "number_of_votes";
+const NUMBER_OF_MULTI_OPTION_VOTES_KEY: &str = "number_of_multi_option_votes";
+// Rounding dust of the reward pools paid to the DAO account so far
+const DAO_REMAINDER_KEY: &str = "dao_remainder";
+const DAO_ACCOUNT_KEY: &str = "dao_account";
+const DEPLOYER_ADDRESS_KEY: &str = "deployer_address";
+// Proves the voting engine role of this contract to the other DAO contracts
+const ENGINE_KEY: &str = "engine_key";
 
 #[casperlabs_contract]
 mod Vote {
 
     #[casperlabs_constructor]
-    fn constructor() {
+    fn constructor(dao_account: AccountHash) {
         set_key(NUMBER_OF_VOTES_KEY, U256::from(0));
+        set_key(NUMBER_OF_MULTI_OPTION_VOTES_KEY, U256::from(0));
+        set_key(DAO_REMAINDER_KEY, U256::from(0));
+        set_key(DAO_ACCOUNT_KEY, dao_account);
+        set_key(DEPLOYER_ADDRESS_KEY, runtime::get_caller());
+        runtime::put_key(ENGINE_KEY, storage::new_uref(()).into());
     }
 
+    // Bootstrap: the governance contract is deployed after the voting engine, the deployer
+    // sets it once and registers the engine key with it
     #[casperlabs_method]
-    fn new_vote(proposal: ProposalSerialized) -> U256 {
-        // WHO CAN CALL THIS FUNCTION?
-        // assert_caller(get_key(REPUTATION_CONTRACT_ADDRESS_KEY));
+    fn register_engine(governance_contract_hash: ContractHash) {
+        assert_caller(get_key(DEPLOYER_ADDRESS_KEY));
+        if (runtime::has_key(GOVERNANCE_CONTRACT_HASH_KEY)) {
+            runtime::revert(Error::EngineAlreadyRegistered);
+        }
+        set_key(GOVERNANCE_CONTRACT_HASH_KEY, governance_contract_hash);
+        let mut register_engine_args: RuntimeArgs = RuntimeArgs::new();
+        register_engine_args.insert("role", Role::VotingEngine as u8);
+        register_engine_args.insert("engine_key", engine_key());
+        runtime::call_contract::<()>(
+            governance_contract_hash,
+            "register_engine",
+            register_engine_args,
+        );
+    }
+
+    #[casperlabs_method]
+    fn new_vote(proposal: ProposalSerialized, engine_key: URef) -> U256 {
+        assert_not_paused(OperationGroup::Proposals);
+        assert_any_engine_role(&[Role::ProposalEngine, Role::ExecutionEngine], engine_key);
         let current_time: u64 = runtime::get_blocktime().into();
         let next_vote_index: U256 = get_key(NUMBER_OF_VOTES_KEY);
-        Voting::new(current_time, proposal)
-            .map(|vote| save_voting(next_vote_index, vote))
-            .map_err(|e| runtime::revert(Error::from(e)))
-            .unwrap();
+        Voting::new(
+            current_time,
+            proposal,
+            internal_get_late_swing_rule(),
+            reputation_member_count_at(current_time),
+            reputation_current_supply_at(current_time),
+        )
+        .map(|vote| save_voting(next_vote_index, vote))
+        .map_err(|e| runtime::revert(Error::from(e)))
+        .unwrap();
         set_key(NUMBER_OF_VOTES_KEY, next_vote_index + 1);
         get_key(NUMBER_OF_VOTES_KEY)
     }
     #[casperlabs_method]
-    fn new_governance_vote(governance_proposal: GovernanceProposalSerialized) -> bool {
-        // WHO CAN CALL THIS FUNCTION?
-        // assert_caller(get_key(REPUTATION_CONTRACT_ADDRESS_KEY));
+    fn new_governance_vote(
+        governance_proposal: GovernanceProposalSerialized,
+        execution_delay: u64,
+        engine_key: URef,
+    ) -> bool {
+        assert_not_paused(OperationGroup::Proposals);
+        assert_any_engine_role(&[Role::ProposalEngine], engine_key);
         let current_time: u64 = runtime::get_blocktime().into();
         let next_vote_index: U256 = get_key(NUMBER_OF_VOTES_KEY);
-        Voting::new_governance(current_time, governance_proposal)
-            .map(|vote| save_voting(next_vote_index, vote))
-            .map_err(|e| runtime::revert(Error::from(e)))
-            .unwrap();
+        Voting::new_governance(
+            current_time,
+            governance_proposal,
+            internal_get_late_swing_rule(),
+            reputation_member_count_at(current_time),
+            reputation_current_supply_at(current_time),
+            execution_delay,
+        )
+        .map(|vote| save_voting(next_vote_index, vote))
+        .map_err(|e| runtime::revert(Error::from(e)))
+        .unwrap();
         set_key(NUMBER_OF_VOTES_KEY, next_vote_index + 1);
         true
     }
@@ -110,9 +163,35 @@ mod Vote {
             args,
         )
     }
+    fn internal_get_settlement_page_size() -> u64 {
+        let mut args: RuntimeArgs = RuntimeArgs::new();
+        runtime::call_contract::<u64>(
+            get_key(GOVERNANCE_CONTRACT_HASH_KEY),
+            "settlement_page_size",
+            args,
+        )
+    }
+    fn internal_get_late_swing_rule() -> LateSwingRule {
+        let mut args: RuntimeArgs = RuntimeArgs::new();
+        LateSwingRule::deserialize(runtime::call_contract::<LateSwingRuleSerialized>(
+            get_key(GOVERNANCE_CONTRACT_HASH_KEY),
+            "late_swing_rule",
+            args,
+        ))
+    }
+    fn internal_get_vote_policies() -> VotePolicies {
+        let args: RuntimeArgs = RuntimeArgs::new();
+        VotePolicies::deserialize(runtime::call_contract::<VotePoliciesSerialized>(
+            get_key(GOVERNANCE_CONTRACT_HASH_KEY),
+            "vote_policies",
+            args,
+        ))
+    }
 
+    // vote_direction: 0 for, 1 against, 2 abstain
     #[casperlabs_method]
-    fn cast_vote(vote_index: U256, reputation_to_stake: U256, vote_direction: bool) {
+    fn cast_vote(vote_index: U256, reputation_to_stake: U256, vote_direction: u8) {
+        assert_not_paused(OperationGroup::Voting);
         assert_member();
         let caller = runtime::get_caller();
         let current_time: u64 = runtime::get_blocktime().into();
@@ -121,37 +200,243 @@ mod Vote {
             runtime::revert(Error::VoteDoesNotExist);
         }
         let mut vote: Voting = Voting::deserialize(vote_serialized);
-        let reputation_contract_hash = internal_get_reputation_contract_hash();
-        let mut balance_of_args: RuntimeArgs = RuntimeArgs::new();
-        balance_of_args.insert("account", caller);
-        let reputation_balance: U256 =
-            runtime::call_contract::<U256>(reputation_contract_hash, "balanceOf", balance_of_args);
-        let committed_reputation = get_key(&committed_reputation_key(&caller));
-        vote.cast_vote(
-            caller,
-            current_time,
-            reputation_balance,
-            reputation_to_stake,
-            committed_reputation,
-            vote_direction,
-        )
-        .map_err(|e| runtime::revert(Error::from(e)))
-        .unwrap();
+        let reputation_balance: U256 = reputation_balance_at(&caller, vote.snapshot_at());
+        // Voting directly overrides the ballot cast by the caller's delegate
+        let revoked_reputation: U256 = vote
+            .revoke_delegated_vote(caller, current_time)
+            .map_err(|e| runtime::revert(Error::from(e)))
+            .unwrap();
+        release_reputation(&caller, VoteType::Binary, vote_index, revoked_reputation);
+        let committed_reputation: U256 = locked_reputation(&caller);
+        // Transition votes are informal and commit no reputation
+        let staked_reputation: U256 = vote
+            .cast_vote(
+                caller,
+                current_time,
+                reputation_balance,
+                reputation_to_stake,
+                committed_reputation,
+                vote_direction.into(),
+            )
+            .map_err(|e| runtime::revert(Error::from(e)))
+            .unwrap();
+        lock_reputation(&caller, VoteType::Binary, vote_index, staked_reputation);
+        // Stake on behalf of the members who delegated to the caller
+        let delegators: Vec<(AccountHash, U256, U256)> =
+            read_delegators_for(&caller, vote.proposal_type, vote.snapshot_at());
+        let delegated_stakes: Vec<(AccountHash, U256)> = vote
+            .cast_delegated_votes(
+                caller,
+                current_time,
+                delegators,
+                staked_reputation,
+                vote_direction.into(),
+            )
+            .map_err(|e| runtime::revert(Error::from(e)))
+            .unwrap();
+        for (delegator, delegated_stake) in delegated_stakes {
+            lock_reputation(&delegator, VoteType::Binary, vote_index, delegated_stake);
+        }
+        save_voting(vote_index, vote);
+    }
+
+    #[casperlabs_method]
+    fn change_vote(vote_index: U256, reputation_to_stake: U256, vote_direction: u8) {
+        assert_not_paused(OperationGroup::Voting);
+        assert_member();
+        let caller = runtime::get_caller();
+        let current_time: u64 = runtime::get_blocktime().into();
+        if (!runtime::has_key(&voting_key(vote_index))) {
+            runtime::revert(Error::VoteDoesNotExist);
+        }
+        let mut vote: Voting = read_voting(vote_index);
+        let reputation_balance: U256 = reputation_balance_at(&caller, vote.snapshot_at());
+        let committed_reputation: U256 = locked_reputation(&caller);
+        let (released_reputation, staked_reputation, released_delegated_reputation): (
+            U256,
+            U256,
+            Vec<(AccountHash, U256)>,
+        ) = vote
+            .change_vote(
+                caller,
+                current_time,
+                reputation_balance,
+                reputation_to_stake,
+                committed_reputation,
+                vote_direction.into(),
+            )
+            .map_err(|e| runtime::revert(Error::from(e)))
+            .unwrap();
+        release_reputation(&caller, VoteType::Binary, vote_index, released_reputation);
+        lock_reputation(&caller, VoteType::Binary, vote_index, staked_reputation);
+        for (delegator, released_stake) in released_delegated_reputation {
+            release_reputation(&delegator, VoteType::Binary, vote_index, released_stake);
+        }
+        // Stake again on behalf of the delegators, following the new ballot
+        let delegators: Vec<(AccountHash, U256, U256)> =
+            read_delegators_for(&caller, vote.proposal_type, vote.snapshot_at());
+        let delegated_stakes: Vec<(AccountHash, U256)> = vote
+            .cast_delegated_votes(
+                caller,
+                current_time,
+                delegators,
+                staked_reputation,
+                vote_direction.into(),
+            )
+            .map_err(|e| runtime::revert(Error::from(e)))
+            .unwrap();
+        for (delegator, delegated_stake) in delegated_stakes {
+            lock_reputation(&delegator, VoteType::Binary, vote_index, delegated_stake);
+        }
         save_voting(vote_index, vote);
-        set_key(
-            &committed_reputation_key(&caller),
-            committed_reputation + reputation_to_stake,
-        );
+    }
+
+    #[casperlabs_method]
+    fn withdraw_vote(vote_index: U256) {
+        assert_not_paused(OperationGroup::Voting);
+        let caller = runtime::get_caller();
+        let current_time: u64 = runtime::get_blocktime().into();
+        if (!runtime::has_key(&voting_key(vote_index))) {
+            runtime::revert(Error::VoteDoesNotExist);
+        }
+        let mut vote: Voting = read_voting(vote_index);
+        let (released_reputation, released_delegated_reputation): (U256, Vec<(AccountHash, U256)>) =
+            vote.withdraw_vote(caller, current_time)
+                .map_err(|e| runtime::revert(Error::from(e)))
+                .unwrap();
+        release_reputation(&caller, VoteType::Binary, vote_index, released_reputation);
+        // The ballots cast on behalf of the caller's delegators are withdrawn with it
+        for (delegator, released_stake) in released_delegated_reputation {
+            release_reputation(&delegator, VoteType::Binary, vote_index, released_stake);
+        }
+        save_voting(vote_index, vote);
+    }
+
+    // Delegate voting power globally, or for a single proposal type
+    #[casperlabs_method]
+    fn delegate(delegate: AccountHash, proposal_type: Option<u8>) {
+        assert_not_paused(OperationGroup::Voting);
+        assert_member();
+        if (!is_member(&delegate)) {
+            runtime::revert(Error::NotAMember);
+        }
+        let caller = runtime::get_caller();
+        let mut delegation: Delegation = read_delegation(&caller);
+        let replaced_delegate: Option<AccountHash> = delegation
+            .delegate(delegate, proposal_type.map(ProposalType::from))
+            .map_err(|e| runtime::revert(Error::from(e)))
+            .unwrap();
+        save_delegation(delegation.clone());
+        add_delegator(&delegate, &caller);
+        if let Some(replaced_delegate) = replaced_delegate {
+            if (!delegation.delegates_to(&replaced_delegate)) {
+                remove_delegator(&replaced_delegate, &caller);
+            }
+        }
+    }
+
+    #[casperlabs_method]
+    fn undelegate(proposal_type: Option<u8>) {
+        assert_not_paused(OperationGroup::Voting);
+        let caller = runtime::get_caller();
+        let mut delegation: Delegation = read_delegation(&caller);
+        let removed_delegate: AccountHash = delegation
+            .undelegate(proposal_type.map(ProposalType::from))
+            .map_err(|e| runtime::revert(Error::from(e)))
+            .unwrap();
+        save_delegation(delegation.clone());
+        if (!delegation.delegates_to(&removed_delegate)) {
+            remove_delegator(&removed_delegate, &caller);
+        }
+    }
+
+    #[casperlabs_method]
+    fn delegation(account: AccountHash) -> DelegationSerialized {
+        read_delegation(&account).serialize()
+    }
+
+    // Secret ballots: lock reputation behind the hash of (voter, vote index, direction, stake,
+    // salt)
+    #[casperlabs_method]
+    fn commit_vote(vote_index: U256, reputation_to_commit: U256, commitment: [u8; 32]) {
+        assert_not_paused(OperationGroup::Voting);
+        assert_member();
+        let caller = runtime::get_caller();
+        let current_time: u64 = runtime::get_blocktime().into();
+        if (!runtime::has_key(&voting_key(vote_index))) {
+            runtime::revert(Error::VoteDoesNotExist);
+        }
+        let mut vote: Voting = read_voting(vote_index);
+        let reputation_balance: U256 = reputation_balance_at(&caller, vote.snapshot_at());
+        let committed_reputation: U256 = locked_reputation(&caller);
+        let locked_reputation: U256 = vote
+            .commit_vote(
+                caller,
+                current_time,
+                reputation_balance,
+                reputation_to_commit,
+                committed_reputation,
+                commitment,
+            )
+            .map_err(|e| runtime::revert(Error::from(e)))
+            .unwrap();
+        save_voting(vote_index, vote);
+        lock_reputation(&caller, VoteType::Binary, vote_index, locked_reputation);
+    }
+
+    // Secret ballots: reveal a committed vote once the commit phase is over
+    #[casperlabs_method]
+    fn reveal_vote(
+        vote_index: U256,
+        vote_direction: u8,
+        reputation_to_stake: U256,
+        salt: [u8; 32],
+    ) {
+        assert_not_paused(OperationGroup::Voting);
+        let caller = runtime::get_caller();
+        let current_time: u64 = runtime::get_blocktime().into();
+        if (!runtime::has_key(&voting_key(vote_index))) {
+            runtime::revert(Error::VoteDoesNotExist);
+        }
+        let mut vote: Voting = read_voting(vote_index);
+        let reputation_balance: U256 = reputation_balance_at(&caller, vote.snapshot_at());
+        let released_reputation: U256 = vote
+            .reveal_vote(
+                caller,
+                vote_index,
+                current_time,
+                reputation_balance,
+                vote_direction.into(),
+                reputation_to_stake,
+                salt,
+            )
+            .map_err(|e| runtime::revert(Error::from(e)))
+            .unwrap();
+        save_voting(vote_index, vote);
+        release_reputation(&caller, VoteType::Binary, vote_index, released_reputation);
     }
 
     #[casperlabs_method]
     fn calculate_vote_outcome(vote_index: U256) -> u8 {
+        assert_not_paused(OperationGroup::Voting);
         let current_time: u64 = runtime::get_blocktime().into();
         let vote_serialized: Option<VotingSerialized> = get_key(&voting_key(vote_index));
         if (vote_serialized == None) {
             runtime::revert(Error::VoteDoesNotExist);
         }
         let mut vote: Voting = Voting::deserialize(vote_serialized.unwrap());
+        let (refunds, forfeits): (Vec<(AccountHash, U256)>, Vec<(AccountHash, U256)>) = vote
+            .settle_unrevealed_commitments(current_time)
+            .map_err(|e| runtime::revert(Error::from(e)))
+            .unwrap();
+        for (voter, refund) in refunds {
+            release_reputation(&voter, VoteType::Binary, vote_index, refund);
+        }
+        // Forfeiters have no ballot to claim, their stake goes to the reward pool
+        for (voter, forfeit) in forfeits {
+            vote.record_uncollected_stake(collect_stake(&voter, forfeit));
+            release_all_reputation(&voter, VoteType::Binary, vote_index);
+        }
         let reputation_allocation_ratio: u64 = internal_get_reputation_allocation_ratio();
         let outcome: VoteResult;
         if vote.proposal_type == ProposalType::Grant
@@ -165,6 +450,22 @@ mod Vote {
             )
             .map_err(|e| runtime::revert(Error::from(e)))
             .unwrap();
+            collect_losing_stakes(&mut vote, VoteType::Binary, vote_index);
+            // Stakes forfeited in a failed vote have no winner to go to
+            pay_rounding_dust(vote.take_rounding_dust());
+            save_voting(vote_index, vote.clone());
+            // The input reputation of an approved grant is minted into the reward pool
+            if (vote.input_reputation > U256::from(0)) {
+                let mut mint_args: RuntimeArgs = RuntimeArgs::new();
+                mint_args.insert("account", voting_engine_address());
+                mint_args.insert("amount", vote.input_reputation);
+                mint_args.insert("engine_key", engine_key());
+                runtime::call_contract::<()>(
+                    internal_get_reputation_contract_hash(),
+                    "mint_reputation",
+                    mint_args,
+                );
+            }
             if (outcome == VoteResult::Approved) {
                 let governance_contract_hash: ContractHash = get_key(GOVERNANCE_CONTRACT_HASH_KEY);
                 let mut args: RuntimeArgs = RuntimeArgs::new();
@@ -176,6 +477,7 @@ mod Vote {
                 if (vote.proposal_type == ProposalType::AnalysisAcceptance) {
                     args.insert("project_index", vote.proposal.unwrap().cost);
                     args.insert("milestone_analysis_index", vote_index);
+                    args.insert("engine_key", engine_key());
                     runtime::call_contract::<bool>(
                         execution_contract_hash,
                         "approve_milestone_analysis",
@@ -183,62 +485,244 @@ mod Vote {
                     );
                 } else {
                     args.insert("proposal", vote.proposal.unwrap().serialize());
+                    args.insert("engine_key", engine_key());
                     runtime::call_contract::<bool>(execution_contract_hash, "new_project", args);
                 }
             }
         } else {
-            // This is a governance proposal
-            let (result, (executed, (key, value))) =
+            // This is a governance proposal, the first call closes the transition vote and
+            // opens the full vote, the second one closes the full vote and queues the actions
+            let result: VoteResult =
                 Voting::calculate_governance_vote_outcome(&mut vote, current_time)
                     .map_err(|e| runtime::revert(Error::from(e)))
                     .unwrap();
-            if (!executed) {
-                let governance_contract_hash: ContractHash = get_key(GOVERNANCE_CONTRACT_HASH_KEY);
-                let voting_engine_address_args: RuntimeArgs = RuntimeArgs::new();
-                let voting_engine_contract_hash: ContractHash = runtime::call_contract(
-                    governance_contract_hash,
-                    "voting_engine_address",
-                    voting_engine_address_args,
-                );
-                let new_variable_key_value: (String, String) = (key, value);
-                let mut governance_args: RuntimeArgs = RuntimeArgs::new();
-                if (new_variable_key_value.0.clone() == "update_policing_ratio") {
-                    let value = new_variable_key_value.1.parse::<u64>().unwrap();
-                    governance_args.insert(new_variable_key_value.0.clone(), value);
-                } else if (new_variable_key_value.0.clone() == "update_reputation_allocation_ratio")
-                {
-                    let value = new_variable_key_value.1.parse::<u64>().unwrap();
-                    governance_args.insert(new_variable_key_value.0.clone(), value);
-                } else if (new_variable_key_value.0.clone() == "update_voting_engine_address") {
-                    let account_hash_with_prefix =
-                        "account-hash-".to_owned() + &new_variable_key_value.1;
-                    let value: AccountHash =
-                        AccountHash::from_formatted_str(&account_hash_with_prefix).unwrap();
-                    governance_args.insert(new_variable_key_value.0.clone(), value);
-                } else if (new_variable_key_value.0.clone() == "update_voting_engine_contract_hash"
-                    || new_variable_key_value.0.clone() == "update_reputation_contract_hash")
-                {
-                    let value: ContractHash = ContractHash::from_formatted_str(
-                        &("contract-".to_owned() + &new_variable_key_value.1),
-                    )
-                    .unwrap();
-                    governance_args.insert(new_variable_key_value.0.clone(), value);
-                }
-                // Execute proposal.
-                runtime::call_contract::<bool>(
-                    voting_engine_contract_hash,
-                    &new_variable_key_value.0,
-                    governance_args,
-                );
-            }
+            collect_losing_stakes(&mut vote, VoteType::Binary, vote_index);
+            pay_rounding_dust(vote.take_rounding_dust());
+            save_voting(vote_index, vote);
             outcome = result;
         }
+        // Push settlement pays the first page right away
+        let settlement_page_size: u64 = internal_get_settlement_page_size();
+        if (settlement_page_size > 0
+            && outcome != VoteResult::InVote
+            && !is_paused(OperationGroup::Claims))
+        {
+            settle_vote_page(vote_index, settlement_page_size);
+        }
         outcome as u8
     }
 
-    // Winning voters call this function to claim their reputation (staked + losers pro-rata)
+    // Anyone carries out the actions of an approved governance proposal once its timelock has
+    // expired, the governance contract applies every action or none for the voting engine only.
+    // Paused with the voting group, except proposals that only lift pauses so governance can
+    // still end an emergency stop. Returns the result of each action
+    #[casperlabs_method]
+    fn execute_governance_proposal(vote_index: U256) -> Vec<u8> {
+        let current_time: u64 = runtime::get_blocktime().into();
+        if (!runtime::has_key(&voting_key(vote_index))) {
+            runtime::revert(Error::VoteDoesNotExist);
+        }
+        let mut vote: Voting = read_voting(vote_index);
+        let governance_actions: Vec<GovernanceAction> = vote
+            .execute_governance_actions(current_time)
+            .map_err(|e| runtime::revert(Error::from(e)))
+            .unwrap();
+        if (!governance_actions
+            .iter()
+            .all(|governance_action| matches!(governance_action, GovernanceAction::Unpause(_))))
+        {
+            assert_not_paused(OperationGroup::Voting);
+        }
+        let serialized_actions: Vec<GovernanceActionSerialized> = governance_actions
+            .iter()
+            .map(|governance_action| governance_action.serialize())
+            .collect();
+        let mut governance_args: RuntimeArgs = RuntimeArgs::new();
+        governance_args.insert("governance_actions", serialized_actions);
+        governance_args.insert("engine_key", engine_key());
+        let action_results: Vec<u8> = runtime::call_contract(
+            get_key(GOVERNANCE_CONTRACT_HASH_KEY),
+            "apply_governance_actions",
+            governance_args,
+        );
+        vote.record_action_results(
+            action_results
+                .iter()
+                .map(|action_result| GovernanceActionResult::from(*action_result))
+                .collect(),
+        );
+        save_voting(vote_index, vote);
+        action_results
+    }
+
+    // The failsafe and compliance accounts can stop queued governance actions until their
+    // timelock expires, the veto is kept with the vote
+    #[casperlabs_method]
+    fn veto_governance_proposal(vote_index: U256) {
+        assert_any_role(&[Role::Failsafe, Role::Compliance]);
+        let current_time: u64 = runtime::get_blocktime().into();
+        if (!runtime::has_key(&voting_key(vote_index))) {
+            runtime::revert(Error::VoteDoesNotExist);
+        }
+        let mut vote: Voting = read_voting(vote_index);
+        vote.veto_governance_actions(runtime::get_caller(), current_time)
+            .map_err(|e| runtime::revert(Error::from(e)))
+            .unwrap();
+        save_voting(vote_index, vote);
+    }
+
+    #[casperlabs_method]
+    fn governance_execution(vote_index: U256) -> Option<GovernanceExecutionSerialized> {
+        if (!runtime::has_key(&voting_key(vote_index))) {
+            runtime::revert(Error::VoteDoesNotExist);
+        }
+        let vote: Voting = read_voting(vote_index);
+        vote.governance_execution
+            .map(|governance_execution| governance_execution.serialize())
+    }
+
+    // Reputation staked (for, against, abstain), abstentions only count toward the quorums
+    #[casperlabs_method]
+    fn vote_tally(vote_index: U256) -> (U256, U256, U256) {
+        if (!runtime::has_key(&voting_key(vote_index))) {
+            runtime::revert(Error::VoteDoesNotExist);
+        }
+        let vote: Voting = read_voting(vote_index);
+        (vote.for_votes, vote.against_votes, vote.abstain_votes)
+    }
+
+    // Dry run, nothing is saved: ballots are (voter, (reputation balance, stake), direction),
+    // returns the projected result and the reputation every voter would gain
+    #[casperlabs_method]
+    fn simulate_vote_outcome(
+        vote_index: U256,
+        ballots: Vec<(AccountHash, (U256, U256), u8)>,
+        finalization_time: u64,
+    ) -> (u8, Vec<(AccountHash, U256)>) {
+        if (!runtime::has_key(&voting_key(vote_index))) {
+            runtime::revert(Error::VoteDoesNotExist);
+        }
+        let vote: Voting = read_voting(vote_index);
+        let hypothetical_ballots: Vec<(AccountHash, U256, U256, VoteDirection)> = ballots
+            .into_iter()
+            .map(|(voter, stakes, vote_direction)| {
+                (voter, stakes.0, stakes.1, vote_direction.into())
+            })
+            .collect();
+        let (result, claimable_reputation) = simulate_vote(
+            &vote,
+            hypothetical_ballots,
+            finalization_time,
+            internal_get_reputation_allocation_ratio(),
+        )
+        .map_err(|e| runtime::revert(Error::from(e)))
+        .unwrap();
+        (result as u8, claimable_reputation.into_iter().collect())
+    }
+
+    // How a closed vote reached its result, None while the full vote is open
+    #[casperlabs_method]
+    fn vote_outcome_report(vote_index: U256) -> Option<VoteOutcomeReportSerialized> {
+        if (!runtime::has_key(&voting_key(vote_index))) {
+            runtime::revert(Error::VoteDoesNotExist);
+        }
+        let vote: Voting = read_voting(vote_index);
+        vote.outcome_report.map(|report| report.serialize())
+    }
+
+    // Voters and the proposer get back exactly what they staked once a vote failed
+    #[casperlabs_method]
+    fn reclaim_stake(vote_index: U256) -> U256 {
+        assert_not_paused(OperationGroup::Claims);
+        let caller = runtime::get_caller();
+        if (!runtime::has_key(&voting_key(vote_index))) {
+            runtime::revert(Error::VoteDoesNotExist);
+        }
+        let mut vote: Voting = read_voting(vote_index);
+        let voter_stake: Result<U256, VotingEngineError> = vote.get_stake(caller);
+        let proposer_stake: Result<U256, VotingEngineError> = vote.get_proposer_stake(caller);
+        if (voter_stake.is_err() && proposer_stake.is_err()) {
+            let error: VotingEngineError = match (voter_stake, proposer_stake) {
+                (Err(VotingEngineError::NoReputationToClaim), Err(proposer_error)) => {
+                    proposer_error
+                }
+                (Err(voter_error), _) => voter_error,
+                _ => VotingEngineError::NoReputationToClaim,
+            };
+            runtime::revert(Error::from(error));
+        }
+        let voter_stake: U256 = voter_stake.unwrap_or(U256::from(0));
+        let proposer_stake: U256 = proposer_stake.unwrap_or(U256::from(0));
+        save_voting(vote_index, vote);
+        release_all_reputation(&caller, VoteType::Binary, vote_index);
+        // The proposer stake was transferred to the voting engine on proposal creation
+        pay_out(&caller, proposer_stake);
+        voter_stake + proposer_stake
+    }
+
+    // Pays the next page of a closed vote's voters, returns true once everyone is settled
+    #[casperlabs_method]
+    fn settle_vote(vote_index: U256) -> bool {
+        assert_not_paused(OperationGroup::Claims);
+        if (!runtime::has_key(&voting_key(vote_index))) {
+            runtime::revert(Error::VoteDoesNotExist);
+        }
+        let settlement_page_size: u64 = internal_get_settlement_page_size();
+        if (settlement_page_size == 0) {
+            runtime::revert(Error::PushSettlementDisabled);
+        }
+        settle_vote_page(vote_index, settlement_page_size)
+    }
+
+    // Claims gains and stake refunds from several closed votes with a single transfer, votes
+    // with nothing to claim are skipped. Returns (vote index, (gained, refunded)) per vote
+    #[casperlabs_method]
+    fn claim_many(vote_indices: Vec<U256>) -> Vec<(U256, (U256, U256))> {
+        assert_not_paused(OperationGroup::Claims);
+        assert_member();
+        let caller = runtime::get_caller();
+        let mut claims: Vec<(U256, (U256, U256))> = Vec::new();
+        let mut transferred_reputation: U256 = U256::from(0);
+        for vote_index in vote_indices {
+            if (!runtime::has_key(&voting_key(vote_index))) {
+                continue;
+            }
+            let mut vote: Voting = read_voting(vote_index);
+            let (gained_reputation, voter_stake, proposer_stake) = vote.claim_all(caller);
+            let refunded_reputation: U256 = voter_stake + proposer_stake;
+            if (gained_reputation == U256::from(0) && refunded_reputation == U256::from(0)) {
+                continue;
+            }
+            pay_rounding_dust(vote.take_rounding_dust());
+            save_voting(vote_index, vote);
+            release_all_reputation(&caller, VoteType::Binary, vote_index);
+            // Voter stakes were only locked, proposer stakes are held by the voting engine
+            transferred_reputation += gained_reputation + proposer_stake;
+            claims.push((vote_index, (gained_reputation, refunded_reputation)));
+        }
+        pay_out(&caller, transferred_reputation);
+        claims
+    }
+
+    #[casperlabs_method]
+    fn dao_remainder() -> U256 {
+        get_key(DAO_REMAINDER_KEY)
+    }
+
+    // Active locks of a member by (vote type, vote index), and the reputation still free to stake
+    #[casperlabs_method]
+    fn reputation_locks(account: AccountHash) -> (ReputationLocksSerialized, U256) {
+        let reputation_locks: ReputationLocks = read_reputation_locks(&account);
+        let free_reputation: U256 =
+            reputation_locks.free_reputation(reputation_balance_of(&account));
+        (reputation_locks.serialize(), free_reputation)
+    }
+
+    // Winning voters call this function to claim their reward (losers pro-rata), their stake
+    // was only locked
     #[casperlabs_method]
     fn claim_reputation(vote_index: U256) {
+        assert_not_paused(OperationGroup::Claims);
         assert_member();
         if (!runtime::has_key(&voting_key(vote_index))) {
             runtime::revert(Error::VoteDoesNotExist);
@@ -248,28 +732,224 @@ mod Vote {
         let gained_reputation: U256 = vote
             .claim_reputation(runtime::get_caller())
             .map_err(|e| runtime::revert(Error::from(e)))
-            .unwrap_or(U256::from(0));
+            .unwrap();
+        pay_rounding_dust(vote.take_rounding_dust());
         save_voting(vote_index, vote);
+        release_all_reputation(&runtime::get_caller(), VoteType::Binary, vote_index);
+        pay_out(&runtime::get_caller(), gained_reputation);
+    }
+
+    // Choose between competing options, method: 0 approval, 1 ranked choice. Quorums are
+    // percentages bounded by the DAO's multi-option vote policy
+    #[casperlabs_method]
+    fn new_multi_option_vote(
+        options: Vec<String>,
+        method: u8,
+        timeout: u64,
+        member_quorum: u64,
+        reputation_quorum: u64,
+    ) -> U256 {
+        assert_not_paused(OperationGroup::Proposals);
+        assert_member();
+        let current_time: u64 = runtime::get_blocktime().into();
+        let next_vote_index: U256 = get_key(NUMBER_OF_MULTI_OPTION_VOTES_KEY);
+        MultiOptionVoting::new(
+            current_time,
+            timeout,
+            MultiOptionMethod::from(method),
+            options,
+            member_quorum,
+            reputation_quorum,
+            internal_get_vote_policies().policy_for(ProposalType::MultiOption, U256::from(0)),
+            reputation_member_count_at(current_time),
+            reputation_current_supply_at(current_time),
+        )
+        .map(|vote| save_multi_option_voting(next_vote_index, vote))
+        .map_err(|e| runtime::revert(Error::from(e)))
+        .unwrap();
+        set_key(NUMBER_OF_MULTI_OPTION_VOTES_KEY, next_vote_index + 1);
+        next_vote_index
+    }
 
-        if (gained_reputation > U256::from(0)) {
-            let reputation_contract_hash: ContractHash = internal_get_reputation_contract_hash();
-            let mut transfer_args: RuntimeArgs = RuntimeArgs::new();
-            transfer_args.insert("recipient", runtime::get_caller());
-            transfer_args.insert("amount", gained_reputation);
-            let transfer_result: bool =
-                runtime::call_contract(reputation_contract_hash, "transfer", transfer_args);
+    // choices: approved options, or options by order of preference
+    #[casperlabs_method]
+    fn cast_multi_option_vote(vote_index: U256, reputation_to_stake: U256, choices: Vec<u8>) {
+        assert_not_paused(OperationGroup::Voting);
+        assert_member();
+        let caller = runtime::get_caller();
+        let current_time: u64 = runtime::get_blocktime().into();
+        if (!runtime::has_key(&multi_option_voting_key(vote_index))) {
+            runtime::revert(Error::VoteDoesNotExist);
         }
+        let mut vote: MultiOptionVoting = read_multi_option_voting(vote_index);
+        let committed_reputation: U256 = locked_reputation(&caller);
+        let staked_reputation: U256 = vote
+            .cast_vote(
+                caller,
+                current_time,
+                reputation_balance_at(&caller, vote.start_at()),
+                reputation_to_stake,
+                committed_reputation,
+                choices,
+            )
+            .map_err(|e| runtime::revert(Error::from(e)))
+            .unwrap();
+        lock_reputation(
+            &caller,
+            VoteType::MultiOption,
+            vote_index,
+            staked_reputation,
+        );
+        save_multi_option_voting(vote_index, vote);
     }
+
+    // Returns the winning option, if any
+    #[casperlabs_method]
+    fn calculate_multi_option_vote_outcome(vote_index: U256) -> Option<u8> {
+        assert_not_paused(OperationGroup::Voting);
+        let current_time: u64 = runtime::get_blocktime().into();
+        if (!runtime::has_key(&multi_option_voting_key(vote_index))) {
+            runtime::revert(Error::VoteDoesNotExist);
+        }
+        let mut vote: MultiOptionVoting = read_multi_option_voting(vote_index);
+        vote.calculate_vote_outcome(current_time)
+            .map_err(|e| runtime::revert(Error::from(e)))
+            .unwrap();
+        let winning_option: Option<u8> = vote.winning_option;
+        // Losing voters have nothing to claim, their stake is debited into the reward pool
+        for (voter, stake) in vote.losing_voters() {
+            vote.record_uncollected_stake(collect_stake(&voter, stake));
+            release_all_reputation(&voter, VoteType::MultiOption, vote_index);
+        }
+        save_multi_option_voting(vote_index, vote);
+        winning_option
+    }
+
+    #[casperlabs_method]
+    fn claim_multi_option_reputation(vote_index: U256) {
+        assert_not_paused(OperationGroup::Claims);
+        assert_member();
+        if (!runtime::has_key(&multi_option_voting_key(vote_index))) {
+            runtime::revert(Error::VoteDoesNotExist);
+        }
+        let mut vote: MultiOptionVoting = read_multi_option_voting(vote_index);
+        let gained_reputation: U256 = vote
+            .claim_reputation(runtime::get_caller())
+            .map_err(|e| runtime::revert(Error::from(e)))
+            .unwrap();
+        pay_rounding_dust(vote.take_rounding_dust());
+        save_multi_option_voting(vote_index, vote);
+        release_all_reputation(&runtime::get_caller(), VoteType::MultiOption, vote_index);
+        pay_out(&runtime::get_caller(), gained_reputation);
+    }
+}
+
+fn has_role(role: Role, account: AccountHash) -> bool {
+    let mut has_role_args: RuntimeArgs = RuntimeArgs::new();
+    has_role_args.insert("role", role as u8);
+    has_role_args.insert("account", account);
+    runtime::call_contract(
+        get_key(GOVERNANCE_CONTRACT_HASH_KEY),
+        "has_role",
+        has_role_args,
+    )
 }
 
-pub fn assert_admin() {
-    let failsafe: AccountHash = get_key("_failSafe");
-    let compliance: AccountHash = get_key("_compliance");
+fn assert_any_role(roles: &[Role]) {
     let caller = runtime::get_caller();
-    if failsafe != caller || compliance != caller {
-        runtime::revert(Error::NotTheAdminAccount);
+    if (!roles.iter().any(|role| has_role(*role, caller))) {
+        runtime::revert(Error::MissingRole);
+    }
+}
+fn has_engine_role(role: Role, engine_key: URef) -> bool {
+    let mut has_engine_role_args: RuntimeArgs = RuntimeArgs::new();
+    has_engine_role_args.insert("role", role as u8);
+    has_engine_role_args.insert("engine_key", engine_key);
+    runtime::call_contract(
+        get_key(GOVERNANCE_CONTRACT_HASH_KEY),
+        "has_engine_role",
+        has_engine_role_args,
+    )
+}
+
+fn assert_any_engine_role(roles: &[Role], engine_key: URef) {
+    if (!roles.iter().any(|role| has_engine_role(*role, engine_key))) {
+        runtime::revert(Error::MissingRole);
+    }
+}
+
+fn engine_key() -> URef {
+    runtime::get_key(ENGINE_KEY)
+        .unwrap_or_revert()
+        .into_uref()
+        .unwrap_or_revert()
+        .into_read()
+}
+
+fn is_paused(operation_group: OperationGroup) -> bool {
+    let mut is_paused_args: RuntimeArgs = RuntimeArgs::new();
+    is_paused_args.insert("operation_group", operation_group as u8);
+    runtime::call_contract(
+        get_key(GOVERNANCE_CONTRACT_HASH_KEY),
+        "is_paused",
+        is_paused_args,
+    )
+}
+
+fn assert_not_paused(operation_group: OperationGroup) {
+    if (is_paused(operation_group)) {
+        runtime::revert(Error::OperationPaused);
     }
 }
+
+fn is_member(account: &AccountHash) -> bool {
+    let reputation_contract_hash: ContractHash = internal_get_reputation_contract_hash();
+    let mut is_member_args: RuntimeArgs = RuntimeArgs::new();
+    is_member_args.insert("account", *account);
+    runtime::call_contract::<bool>(reputation_contract_hash, "is_member", is_member_args)
+}
+
+fn reputation_member_count_at(timestamp: u64) -> u64 {
+    let reputation_contract_hash: ContractHash = internal_get_reputation_contract_hash();
+    let mut member_count_args: RuntimeArgs = RuntimeArgs::new();
+    member_count_args.insert("timestamp", timestamp);
+    runtime::call_contract::<u64>(
+        reputation_contract_hash,
+        "member_count_at",
+        member_count_args,
+    )
+}
+
+fn reputation_current_supply_at(timestamp: u64) -> U256 {
+    let reputation_contract_hash: ContractHash = internal_get_reputation_contract_hash();
+    let mut current_supply_args: RuntimeArgs = RuntimeArgs::new();
+    current_supply_args.insert("timestamp", timestamp);
+    runtime::call_contract::<U256>(
+        reputation_contract_hash,
+        "current_supply_at",
+        current_supply_args,
+    )
+}
+
+fn reputation_balance_of(account: &AccountHash) -> U256 {
+    let reputation_contract_hash: ContractHash = internal_get_reputation_contract_hash();
+    let mut balance_of_args: RuntimeArgs = RuntimeArgs::new();
+    balance_of_args.insert("account", *account);
+    runtime::call_contract::<U256>(reputation_contract_hash, "balance_of", balance_of_args)
+}
+
+// Voting power: reputation received after the snapshot does not count, reputation spent
+// since then cannot be staked
+fn reputation_balance_at(account: &AccountHash, timestamp: u64) -> U256 {
+    let reputation_contract_hash: ContractHash = internal_get_reputation_contract_hash();
+    let mut balance_of_args: RuntimeArgs = RuntimeArgs::new();
+    balance_of_args.insert("account", *account);
+    balance_of_args.insert("timestamp", timestamp);
+    let snapshot_balance: U256 =
+        runtime::call_contract::<U256>(reputation_contract_hash, "balance_of_at", balance_of_args);
+    core::cmp::min(snapshot_balance, reputation_balance_of(account))
+}
+
 pub fn assert_member() {
     let caller = runtime::get_caller();
     let reputation_contract_hash: ContractHash = internal_get_reputation_contract_hash();
@@ -327,8 +1007,191 @@ fn voting_key(index: U256) -> String {
     format!("_votes_{}", index)
 }
 
-fn committed_reputation_key(account: &AccountHash) -> String {
-    format!("_committed_reputation_{}", account)
+fn save_multi_option_voting(index: U256, vote: MultiOptionVoting) {
+    set_key(&multi_option_voting_key(index), vote.serialize());
+}
+
+fn read_multi_option_voting(index: U256) -> MultiOptionVoting {
+    MultiOptionVoting::deserialize(get_key(&multi_option_voting_key(index)))
+}
+
+fn multi_option_voting_key(index: U256) -> String {
+    format!("_multi_option_votes_{}", index)
+}
+
+fn read_reputation_locks(account: &AccountHash) -> ReputationLocks {
+    if (!runtime::has_key(&reputation_locks_key(account))) {
+        return ReputationLocks::new(*account);
+    }
+    ReputationLocks::deserialize(get_key(&reputation_locks_key(account)))
+}
+
+fn save_reputation_locks(reputation_locks: ReputationLocks) {
+    set_key(
+        &reputation_locks_key(&reputation_locks.account),
+        reputation_locks.serialize(),
+    );
+}
+
+fn locked_reputation(account: &AccountHash) -> U256 {
+    read_reputation_locks(account).locked_reputation()
+}
+
+fn lock_reputation(account: &AccountHash, vote_type: VoteType, vote_index: U256, amount: U256) {
+    let mut reputation_locks: ReputationLocks = read_reputation_locks(account);
+    reputation_locks.lock(vote_type, vote_index, amount);
+    save_reputation_locks(reputation_locks);
+}
+
+fn release_reputation(account: &AccountHash, vote_type: VoteType, vote_index: U256, amount: U256) {
+    let mut reputation_locks: ReputationLocks = read_reputation_locks(account);
+    reputation_locks.release(vote_type, vote_index, amount);
+    save_reputation_locks(reputation_locks);
+}
+
+fn release_all_reputation(account: &AccountHash, vote_type: VoteType, vote_index: U256) {
+    let mut reputation_locks: ReputationLocks = read_reputation_locks(account);
+    reputation_locks.release_all(vote_type, vote_index);
+    save_reputation_locks(reputation_locks);
+}
+
+// Stakes and rewards are held by the voting engine address, voter stakes are only locked
+// until the vote closes and the losing ones are debited into the reward pool then
+fn voting_engine_address() -> AccountHash {
+    runtime::call_contract::<AccountHash>(
+        get_key(GOVERNANCE_CONTRACT_HASH_KEY),
+        "voting_engine_address",
+        RuntimeArgs::new(),
+    )
+}
+
+fn pay_out(recipient: &AccountHash, amount: U256) {
+    if (amount == U256::from(0)) {
+        return;
+    }
+    let mut transfer_args: RuntimeArgs = RuntimeArgs::new();
+    transfer_args.insert("from", voting_engine_address());
+    transfer_args.insert("to", *recipient);
+    transfer_args.insert("amount", amount);
+    transfer_args.insert("engine_key", engine_key());
+    runtime::call_contract::<bool>(
+        internal_get_reputation_contract_hash(),
+        "transferFrom",
+        transfer_args,
+    );
+}
+
+// Debits a lost stake into the reward pool, returns what the voter no longer holds, which is
+// left out of it
+fn collect_stake(voter: &AccountHash, stake: U256) -> U256 {
+    let collected_stake: U256 = core::cmp::min(stake, reputation_balance_of(voter));
+    if (collected_stake > U256::from(0)) {
+        let mut transfer_args: RuntimeArgs = RuntimeArgs::new();
+        transfer_args.insert("from", *voter);
+        transfer_args.insert("to", voting_engine_address());
+        transfer_args.insert("amount", collected_stake);
+        transfer_args.insert("engine_key", engine_key());
+        runtime::call_contract::<bool>(
+            internal_get_reputation_contract_hash(),
+            "transferFrom",
+            transfer_args,
+        );
+    }
+    stake - collected_stake
+}
+
+// Losing voters have nothing to claim, their stake is debited and their lock ends with the vote
+fn collect_losing_stakes(vote: &mut Voting, vote_type: VoteType, vote_index: U256) {
+    for (voter, stake) in vote.losing_voters() {
+        vote.record_uncollected_stake(collect_stake(&voter, stake));
+        release_all_reputation(&voter, vote_type, vote_index);
+    }
+}
+
+// Pays the reward pool leftovers of a fully claimed vote to the DAO account
+fn pay_rounding_dust(rounding_dust: U256) {
+    if (rounding_dust > U256::from(0)) {
+        pay_out(&get_key(DAO_ACCOUNT_KEY), rounding_dust);
+        let dao_remainder: U256 = get_key(DAO_REMAINDER_KEY);
+        set_key(DAO_REMAINDER_KEY, dao_remainder + rounding_dust);
+    }
+}
+
+fn settle_vote_page(vote_index: U256, page_size: u64) -> bool {
+    let mut vote: Voting = read_voting(vote_index);
+    let (payouts, settled): (Vec<(AccountHash, U256)>, bool) = vote
+        .settle_page(page_size)
+        .map_err(|e| runtime::revert(Error::from(e)))
+        .unwrap();
+    pay_rounding_dust(vote.take_rounding_dust());
+    save_voting(vote_index, vote);
+    for (account, payout) in payouts {
+        release_all_reputation(&account, VoteType::Binary, vote_index);
+        pay_out(&account, payout);
+    }
+    settled
+}
+
+fn read_delegation(account: &AccountHash) -> Delegation {
+    if (!runtime::has_key(&delegation_key(account))) {
+        return Delegation::new(*account);
+    }
+    Delegation::deserialize(get_key(&delegation_key(account)))
+}
+
+fn save_delegation(delegation: Delegation) {
+    set_key(
+        &delegation_key(&delegation.delegator),
+        delegation.serialize(),
+    );
+}
+
+fn add_delegator(delegate: &AccountHash, delegator: &AccountHash) {
+    let mut delegators: Vec<AccountHash> = get_key(&delegators_key(delegate));
+    if (!delegators.contains(delegator)) {
+        delegators.push(*delegator);
+        set_key(&delegators_key(delegate), delegators);
+    }
+}
+
+fn remove_delegator(delegate: &AccountHash, delegator: &AccountHash) {
+    let mut delegators: Vec<AccountHash> = get_key(&delegators_key(delegate));
+    delegators.retain(|account| account != delegator);
+    set_key(&delegators_key(delegate), delegators);
+}
+
+// (delegator, snapshot reputation balance, committed reputation) of the members whose delegate for
+// `proposal_type` is `delegate`
+fn read_delegators_for(
+    delegate: &AccountHash,
+    proposal_type: ProposalType,
+    snapshot_timestamp: u64,
+) -> Vec<(AccountHash, U256, U256)> {
+    let mut output: Vec<(AccountHash, U256, U256)> = Vec::new();
+    let delegators: Vec<AccountHash> = get_key(&delegators_key(delegate));
+    for delegator in delegators {
+        if (read_delegation(&delegator).delegate_for(proposal_type) != Some(*delegate)) {
+            continue;
+        }
+        output.push((
+            delegator,
+            reputation_balance_at(&delegator, snapshot_timestamp),
+            locked_reputation(&delegator),
+        ));
+    }
+    output
+}
+
+fn delegation_key(account: &AccountHash) -> String {
+    format!("_delegations_{}", account)
+}
+
+fn delegators_key(account: &AccountHash) -> String {
+    format!("_delegators_{}", account)
+}
+
+fn reputation_locks_key(account: &AccountHash) -> String {
+    format!("_reputation_locks_{}", account)
 }
 
 fn member_key(account: &AccountHash) -> String {
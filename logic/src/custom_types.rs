@@ -40,6 +40,9 @@ pub mod custom_types {
             // cost
             U256, //0.2
         ),
+        // 2
+        // Proposer stake
+        U256,
     );
 
     pub type MilestoneSerialized = (
@@ -52,7 +55,19 @@ pub mod custom_types {
     pub type FundingTrancheSerialized = (u8, U256, U256);
 
     pub type RatiosSerialized = (u64, u64, u64);
-    pub type VoteConfigurationSerialized = ((u64, U256), (u8, u64, U256));
+    pub type VoteConfigurationSerialized = (
+        // member quorum, reputation quorum (percentages), counting rule
+        (u64, u64, u8),
+        // threshold, timeout, voter staking limits
+        (u8, u64, StakingLimitsSerialized),
+        Option<SecretBallotConfigurationSerialized>,
+    );
+
+    // reveal timeout, unrevealed policy
+    pub type SecretBallotConfigurationSerialized = (u64, u8);
+
+    // share of the voter's balance, share of the vote's total stake, in basis points
+    pub type StakingLimitsSerialized = (u64, Option<u64>);
 
     pub type GovernanceProposalSerialized = (
         // 0
@@ -71,14 +86,28 @@ pub mod custom_types {
             // 0.2
             // proposal status
             u8,
-            // new_variable_key_value
-            (String, String),
+            // governance actions
+            Vec<GovernanceActionSerialized>,
+            // proposer stake
+            U256,
         ),
     );
 
-    pub type GovernanceVoteConfigurationSerialized = ((U256, u64, String), (U256, u64, u64));
+    pub type GovernanceVoteConfigurationSerialized = (
+        // transition vote quorum (percentage), threshold, proposal repository address
+        (u64, u64, String),
+        // full vote quorum (percentage), threshold, timeout
+        (u64, u64, u64),
+        // full vote timeout, secret ballot, (counting rule, voter staking limits)
+        (
+            u64,
+            Option<SecretBallotConfigurationSerialized>,
+            (u8, StakingLimitsSerialized),
+        ),
+    );
 
-    pub type VotingDataSerialized = (U256, bool, bool);
+    // reputation staked, vote direction, (claimed, delegate)
+    pub type VotingDataSerialized = (U256, u8, (bool, Option<[u8; 32]>));
 
     pub type VotersSerialized = BTreeMap<[u8; 32], VotingDataSerialized>;
 
@@ -91,11 +120,115 @@ pub mod custom_types {
                 Option<GovernanceProposalSerialized>,
                 u8,
             ),
+            LateSwingSerialized,
+        ),
+        // (for votes, against votes, (input reputation, (distributed rewards, uncollected
+        // stakes), dust collected)), transition vote, secret ballot
+        (
+            (U256, U256, (U256, (U256, U256), bool)),
+            TransitionVoteSerialized,
+            SecretBallotSerialized,
+        ),
+        // (for voters, against voters, abstain voters),
+        // (abstain votes, proposer stake reclaimed, outcome report),
+        // (result, (snapshot, eligible members), (eligible reputation, governance execution))
+        (
+            (VotersSerialized, VotersSerialized, VotersSerialized),
+            (U256, bool, Option<VoteOutcomeReportSerialized>),
+            (
+                u8,
+                (u64, u64),
+                (U256, Option<GovernanceExecutionSerialized>),
+            ),
+        ),
+    );
+
+    // (transition for votes, transition against votes, full vote start), transition voters
+    pub type TransitionVoteSerialized = ((U256, U256, u64), VotersSerialized);
+
+    // window, extension, max extensions
+    pub type LateSwingRuleSerialized = (u64, u64, u64);
+
+    // end timestamp, late swing rule, (extensions, leading side)
+    pub type LateSwingSerialized = (u64, LateSwingRuleSerialized, (u64, Option<bool>));
+
+    // commitment, reputation committed, (revealed, settled)
+    pub type CommitmentSerialized = ([u8; 32], U256, (bool, bool));
+
+    pub type CommitmentsSerialized = BTreeMap<[u8; 32], CommitmentSerialized>;
+
+    // commitments, forfeited reputation
+    pub type SecretBallotSerialized = (CommitmentsSerialized, U256);
+
+    // reputation staked, choices, claimed
+    pub type MultiOptionBallotSerialized = (U256, Vec<u8>, bool);
+
+    pub type MultiOptionBallotsSerialized = BTreeMap<[u8; 32], MultiOptionBallotSerialized>;
+
+    pub type MultiOptionVotingSerialized = (
+        (
+            // start, end, method
+            (u64, u64, u8),
+            // options, member quorum, reputation quorum
+            (Vec<String>, u64, u64),
+            // eligible members, eligible reputation
+            (u64, U256),
+        ),
+        (
+            // total members, total staked reputation, winning option
+            (u64, U256, Option<u8>),
+            // result, eliminated options, (winning stake, losing stake)
+            (u8, Vec<u8>, (U256, U256)),
+            // ballots, option votes
+            (MultiOptionBallotsSerialized, Vec<U256>),
         ),
+        // uncollected stakes, distributed rewards, dust collected
+        (U256, U256, bool),
+    );
+
+    // delegator, global delegate, proposal type delegates
+    pub type DelegationSerialized = ([u8; 32], Option<[u8; 32]>, BTreeMap<u8, [u8; 32]>);
+
+    pub type VoteOutcomeReportSerialized = (
+        // result, (member quorum met, reputation quorum met, threshold met),
+        // (total members, eligible members)
+        (u8, (bool, bool, bool), (u64, u64)),
+        // for, against, abstain votes
         (U256, U256, U256),
-        (VotersSerialized, VotersSerialized, u8),
+        // (member participation, reputation participation, for percentage), input reputation,
+        // result of each governance action
+        ((u64, u64, u64), U256, Vec<u8>),
     );
 
+    // kind, values, (account or contract hash, amount, vote policy), unused fields are zero
+    pub type GovernanceActionSerialized =
+        (u8, (u64, u64, u64), ([u8; 32], U256, VotePolicySerialized));
+
+    // accounts by role
+    pub type RoleRegistrySerialized = BTreeMap<u8, Vec<[u8; 32]>>;
+
+    // paused until, by operation group
+    pub type PauseStateSerialized = BTreeMap<u8, u64>;
+
+    // (execution delay, earliest execution), status, (vetoer, vetoed at)
+    pub type GovernanceExecutionSerialized = ((u64, u64), u8, Option<([u8; 32], u64)>);
+
+    pub type VotePolicySerialized = (
+        // min member quorum, min reputation quorum, min threshold
+        (u64, u64, u64),
+        // min duration, max duration
+        (u64, u64),
+        // ((min, max balance share), (min, max total stake share)), allowed counting rules,
+        // (secret ballot allowed, min reveal timeout)
+        (((u64, u64), (u64, u64)), u8, (bool, u64)),
+    );
+
+    // policies by (proposal type, lowest cost of the band)
+    pub type VotePoliciesSerialized = BTreeMap<(u8, U256), VotePolicySerialized>;
+
+    // account, locked reputation by (vote type, vote index)
+    pub type ReputationLocksSerialized = ([u8; 32], BTreeMap<(u8, U256), U256>);
+
     pub type MilestoneAnalysisSerialized = (bool, BTreeMap<String, String>);
     pub type ProjectSerialized = (
         (ProposalSerialized, u64),
@@ -0,0 +1,138 @@
+extern crate alloc;
+use crate::{custom_types::custom_types::DelegationSerialized, error::*, proposal::ProposalType};
+use alloc::collections::BTreeMap;
+use core::cmp::{Eq, Ord, PartialEq, PartialOrd};
+use types::account::AccountHash;
+
+// Who votes on behalf of a member, a proposal type delegate takes precedence over the global one
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone)]
+pub struct Delegation {
+    pub delegator: AccountHash,
+    pub global_delegate: Option<AccountHash>,
+    pub proposal_type_delegates: BTreeMap<ProposalType, AccountHash>,
+}
+
+impl Delegation {
+    pub fn new(delegator: AccountHash) -> Delegation {
+        Delegation {
+            delegator,
+            global_delegate: None,
+            proposal_type_delegates: BTreeMap::new(),
+        }
+    }
+
+    // Delegates globally when `proposal_type` is None, returns the replaced delegate
+    pub fn delegate(
+        &mut self,
+        delegate: AccountHash,
+        proposal_type: Option<ProposalType>,
+    ) -> Result<Option<AccountHash>, VotingEngineError> {
+        if delegate == self.delegator {
+            return Err(VotingEngineError::CannotDelegateToSelf);
+        }
+        match proposal_type {
+            Some(proposal_type) => Ok(self.proposal_type_delegates.insert(proposal_type, delegate)),
+            None => Ok(self.global_delegate.replace(delegate)),
+        }
+    }
+
+    // Returns the removed delegate
+    pub fn undelegate(
+        &mut self,
+        proposal_type: Option<ProposalType>,
+    ) -> Result<AccountHash, VotingEngineError> {
+        let removed_delegate: Option<AccountHash> = match proposal_type {
+            Some(proposal_type) => self.proposal_type_delegates.remove(&proposal_type),
+            None => self.global_delegate.take(),
+        };
+        removed_delegate.ok_or(VotingEngineError::NotDelegated)
+    }
+
+    pub fn delegate_for(&self, proposal_type: ProposalType) -> Option<AccountHash> {
+        match self.proposal_type_delegates.get(&proposal_type) {
+            Some(delegate) => Some(*delegate),
+            None => self.global_delegate,
+        }
+    }
+
+    // Whether `delegate` votes for this member on any proposal type
+    pub fn delegates_to(&self, delegate: &AccountHash) -> bool {
+        self.global_delegate.as_ref() == Some(delegate)
+            || self
+                .proposal_type_delegates
+                .values()
+                .any(|proposal_type_delegate| proposal_type_delegate == delegate)
+    }
+
+    pub fn serialize(&self) -> DelegationSerialized {
+        let mut proposal_type_delegates_output: BTreeMap<u8, [u8; 32]> = BTreeMap::new();
+        for (proposal_type, delegate) in self.proposal_type_delegates.iter() {
+            proposal_type_delegates_output.insert(*proposal_type as u8, delegate.value());
+        }
+        (
+            self.delegator.value(),
+            self.global_delegate.map(|delegate| delegate.value()),
+            proposal_type_delegates_output,
+        )
+    }
+
+    pub fn deserialize(serialized_delegation: DelegationSerialized) -> Delegation {
+        let mut proposal_type_delegates: BTreeMap<ProposalType, AccountHash> = BTreeMap::new();
+        for (proposal_type, delegate) in serialized_delegation.2 {
+            proposal_type_delegates.insert(proposal_type.into(), AccountHash::new(delegate));
+        }
+        Delegation {
+            delegator: AccountHash::new(serialized_delegation.0),
+            global_delegate: serialized_delegation.1.map(AccountHash::new),
+            proposal_type_delegates,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ALI: AccountHash = AccountHash::new([1u8; 32]);
+    const BOB: AccountHash = AccountHash::new([2u8; 32]);
+    const JON: AccountHash = AccountHash::new([3u8; 32]);
+
+    #[test]
+    fn test_proposal_type_delegate_takes_precedence() {
+        let mut delegation = Delegation::new(ALI);
+        assert_eq!(delegation.delegate(BOB, None), Ok(None));
+        assert_eq!(
+            delegation.delegate(JON, Some(ProposalType::Governance)),
+            Ok(None)
+        );
+        assert_eq!(delegation.delegate_for(ProposalType::Grant), Some(BOB));
+        assert_eq!(delegation.delegate_for(ProposalType::Governance), Some(JON));
+        assert!(delegation.delegates_to(&BOB));
+        assert!(delegation.delegates_to(&JON));
+
+        assert_eq!(
+            delegation.undelegate(Some(ProposalType::Governance)),
+            Ok(JON)
+        );
+        assert_eq!(delegation.delegate_for(ProposalType::Governance), Some(BOB));
+        assert_eq!(delegation.undelegate(None), Ok(BOB));
+        assert_eq!(delegation.delegate_for(ProposalType::Grant), None);
+        assert_eq!(
+            delegation.undelegate(None),
+            Err(VotingEngineError::NotDelegated)
+        );
+    }
+
+    #[test]
+    fn test_delegate_replaces_previous_delegate() {
+        let mut delegation = Delegation::new(ALI);
+        assert_eq!(
+            delegation.delegate(ALI, None),
+            Err(VotingEngineError::CannotDelegateToSelf)
+        );
+        delegation.delegate(BOB, None).unwrap();
+        assert_eq!(delegation.delegate(JON, None), Ok(Some(BOB)));
+        assert!(!delegation.delegates_to(&BOB));
+        assert_eq!(Delegation::deserialize(delegation.serialize()), delegation);
+    }
+}
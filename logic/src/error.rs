@@ -1,6 +1,3 @@
-#[derive(PartialEq, Debug)]
-pub struct StartNotBeforeEnd;
-
 #[derive(PartialEq, Debug)]
 pub enum VotingEngineError {
     VotingNotStarted,
@@ -14,7 +11,26 @@ pub enum VotingEngineError {
     VoteFailed,
     InvalidReputationToStake,
     StakingLimitReached,
+    TotalStakeLimitReached,
+    NoReputationAtSnapshot,
     AlreadyVoted,
+    VoteIsSecret,
+    VoteIsNotSecret,
+    RevealNotOngoing,
+    NoCommitment,
+    AlreadyRevealed,
+    InvalidReveal,
+    CannotDelegateToSelf,
+    NotDelegated,
+    NotVoted,
+    InvalidOptions,
+    InvalidChoices,
+    ExecutionNotQueued,
+    TimelockNotExpired,
+    VetoWindowClosed,
+    NoVotePolicy,
+    OutsideVotePolicy,
+    OutcomeLocked,
 }
 #[derive(PartialEq, Debug)]
 pub enum ProposalError {
@@ -23,7 +39,15 @@ pub enum ProposalError {
     StakedRepGreaterThanReputationBalance,
     ProjectCostNotEqualToMilestonesSum,
     InvalidMilestonesProgressPercentages,
+    QuorumBelowPolicy,
+    ThresholdBelowPolicy,
+    DurationOutsidePolicy,
+    UnknownGovernanceAction,
+    GovernanceValueOutOfRange,
+    InvalidNumberOfGovernanceActions,
+    NoVotePolicy,
+    StakingLimitsBelowPolicy,
+    CountingRuleNotAllowed,
+    SecretBallotOutsidePolicy,
+    StakingLimitsAbovePolicy,
 }
-
-#[derive(PartialEq, Debug)]
-pub struct NewVotingPowerBelowUsed;
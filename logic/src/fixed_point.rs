@@ -0,0 +1,93 @@
+extern crate alloc;
+use core::cmp::min;
+use types::U256;
+
+pub const BASIS_POINTS: u64 = 10000;
+
+// value * numerator / denominator rounded down, nothing when the denominator is zero
+pub fn mul_div(value: U256, numerator: U256, denominator: U256) -> U256 {
+    if denominator == U256::from(0) {
+        return U256::from(0);
+    }
+    (value * numerator) / denominator
+}
+
+pub fn basis_points_of(value: U256, basis_points: u64) -> U256 {
+    mul_div(
+        value,
+        U256::from(min(basis_points, BASIS_POINTS)),
+        U256::from(BASIS_POINTS),
+    )
+}
+
+// Share of `pool` owed to `stake` out of `total_stake`, rounded down so the shares never add up
+// to more than the pool
+pub fn pro_rata(pool: U256, stake: U256, total_stake: U256) -> U256 {
+    mul_div(pool, min(stake, total_stake), total_stake)
+}
+
+// `percentage` of `value` rounded up, so a quorum is never met by a fraction of a member or of
+// a reputation unit
+pub fn percentage_of_rounded_up(value: U256, percentage: u64) -> U256 {
+    let product: U256 = value * U256::from(percentage);
+    let quotient: U256 = product / U256::from(100);
+    if product % U256::from(100) == U256::from(0) {
+        quotient
+    } else {
+        quotient + U256::from(1)
+    }
+}
+
+pub fn percentage_to_basis_points(percentage: u64) -> u64 {
+    min(percentage, 100) * 100
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_mul_div_rounds_down() {
+        assert_eq!(
+            mul_div(U256::from(10), U256::from(2), U256::from(3)),
+            U256::from(6)
+        );
+        assert_eq!(
+            mul_div(U256::from(10), U256::from(2), U256::from(0)),
+            U256::from(0)
+        );
+    }
+
+    #[test]
+    fn test_basis_points_are_capped() {
+        assert_eq!(basis_points_of(U256::from(999), 2500), U256::from(249));
+        assert_eq!(basis_points_of(U256::from(999), 0), U256::from(0));
+        assert_eq!(basis_points_of(U256::from(999), 20000), U256::from(999));
+        assert_eq!(percentage_to_basis_points(50), 5000);
+        assert_eq!(percentage_to_basis_points(150), BASIS_POINTS);
+    }
+
+    #[test]
+    fn test_percentage_of_rounded_up() {
+        assert_eq!(percentage_of_rounded_up(U256::from(3), 34), U256::from(2));
+        assert_eq!(
+            percentage_of_rounded_up(U256::from(300), 34),
+            U256::from(102)
+        );
+        assert_eq!(percentage_of_rounded_up(U256::from(3), 0), U256::from(0));
+    }
+
+    #[test]
+    fn test_pro_rata_shares_never_exceed_the_pool() {
+        let pool: U256 = U256::from(100);
+        let stakes: [u64; 3] = [1, 1, 1];
+        let shares: U256 = stakes
+            .iter()
+            .map(|stake| pro_rata(pool, U256::from(*stake), U256::from(3)))
+            .fold(U256::from(0), |total, share| total + share);
+        assert_eq!(shares, U256::from(99));
+        // A stake above the total is capped to the whole pool
+        assert_eq!(pro_rata(pool, U256::from(5), U256::from(3)), pool);
+        assert_eq!(pro_rata(pool, U256::from(5), U256::from(0)), U256::from(0));
+    }
+}
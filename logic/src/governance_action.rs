@@ -0,0 +1,453 @@
+extern crate alloc;
+use crate::{
+    custom_types::custom_types::GovernanceActionSerialized,
+    error::*,
+    fixed_point::BASIS_POINTS,
+    pause::{OperationGroup, PauseState, NUMBER_OF_OPERATION_GROUPS},
+    proposal::ProposalType,
+    roles::{Role, NUMBER_OF_ROLES},
+    vote_policy::VotePolicy,
+};
+use alloc::vec::Vec;
+use core::cmp::{Eq, Ord, PartialEq, PartialOrd};
+use types::{account::AccountHash, ContractHash, U256};
+
+// 12 decimals, 10^12 allocates one reputation per unit of cost
+pub const MAX_REPUTATION_ALLOCATION_RATIO: u64 = 1_000_000_000_000;
+pub const MAX_SETTLEMENT_PAGE_SIZE: u64 = 100;
+pub const MAX_LATE_SWING_EXTENSIONS: u64 = 10;
+pub const MAX_GOVERNANCE_ACTIONS: usize = 10;
+
+// Every parameter governance can change, with its value
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone, Copy)]
+pub enum GovernanceAction {
+    // Percentage of the input reputation shared among the voters
+    PolicingRatio(u64),
+    ReputationAllocationRatio(u64),
+    // 0 disables push settlement
+    SettlementPageSize(u64),
+    // window, extension, max extensions
+    LateSwingRule(u64, u64, u64),
+    // proposal type, lowest cost of the band, policy
+    VotePolicy(ProposalType, U256, VotePolicy),
+    VotingEngineAddress(AccountHash),
+    VotingEngineContractHash(ContractHash),
+    ReputationContractHash(ContractHash),
+    ExecutionContractHash(ContractHash),
+    // operation group, duration
+    Pause(OperationGroup, u64),
+    Unpause(OperationGroup),
+    GrantRole(Role, AccountHash),
+    RevokeRole(Role, AccountHash),
+}
+
+// What became of each action of an approved governance proposal
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone, Copy)]
+pub enum GovernanceActionResult {
+    Pending,
+    Applied,
+    Invalid,
+    // Valid, but another action of the proposal was not
+    NotApplied,
+}
+
+impl GovernanceAction {
+    // Rejects values the contracts could not work with
+    pub fn validate(&self) -> Result<(), ProposalError> {
+        let valid: bool = match *self {
+            GovernanceAction::PolicingRatio(policing_ratio) => policing_ratio <= 100,
+            GovernanceAction::ReputationAllocationRatio(reputation_allocation_ratio) => {
+                reputation_allocation_ratio <= MAX_REPUTATION_ALLOCATION_RATIO
+            }
+            GovernanceAction::SettlementPageSize(settlement_page_size) => {
+                settlement_page_size <= MAX_SETTLEMENT_PAGE_SIZE
+            }
+            GovernanceAction::LateSwingRule(window, extension, max_extensions) => {
+                (window == 0 || extension > 0) && max_extensions <= MAX_LATE_SWING_EXTENSIONS
+            }
+            GovernanceAction::VotePolicy(_, _, vote_policy) => {
+                vote_policy.min_member_quorum <= 100
+                    && vote_policy.min_reputation_quorum <= 100
+                    && vote_policy.min_threshold <= 100
+                    && vote_policy.min_duration <= vote_policy.max_duration
+                    && vote_policy.min_balance_share <= vote_policy.max_balance_share
+                    && vote_policy.max_balance_share <= BASIS_POINTS
+                    && vote_policy.min_total_stake_share <= vote_policy.max_total_stake_share
+                    && vote_policy.max_total_stake_share <= BASIS_POINTS
+                    && vote_policy.counting_rules != 0
+            }
+            GovernanceAction::VotingEngineAddress(voting_engine_address) => {
+                voting_engine_address.value() != [0; 32]
+            }
+            GovernanceAction::VotingEngineContractHash(contract_hash)
+            | GovernanceAction::ReputationContractHash(contract_hash)
+            | GovernanceAction::ExecutionContractHash(contract_hash) => {
+                contract_hash.value() != [0; 32]
+            }
+            GovernanceAction::Pause(_, duration) => PauseState::is_valid_duration(duration),
+            GovernanceAction::Unpause(_) => true,
+            GovernanceAction::GrantRole(_, account) | GovernanceAction::RevokeRole(_, account) => {
+                account.value() != [0; 32]
+            }
+        };
+        if !valid {
+            return Err(ProposalError::GovernanceValueOutOfRange);
+        }
+        Ok(())
+    }
+
+    pub fn serialize(&self) -> GovernanceActionSerialized {
+        let no_policy: VotePolicy = VotePolicy {
+            min_member_quorum: 0,
+            min_reputation_quorum: 0,
+            min_threshold: 0,
+            min_duration: 0,
+            max_duration: 0,
+            min_balance_share: 0,
+            max_balance_share: 0,
+            min_total_stake_share: 0,
+            max_total_stake_share: 0,
+            counting_rules: 0,
+            allow_secret_ballot: false,
+            min_reveal_timeout: 0,
+        };
+        let (kind, values, (hash, amount, vote_policy)) = match *self {
+            GovernanceAction::PolicingRatio(policing_ratio) => (
+                0,
+                (policing_ratio, 0, 0),
+                ([0; 32], U256::from(0), no_policy),
+            ),
+            GovernanceAction::ReputationAllocationRatio(reputation_allocation_ratio) => (
+                1,
+                (reputation_allocation_ratio, 0, 0),
+                ([0; 32], U256::from(0), no_policy),
+            ),
+            GovernanceAction::SettlementPageSize(settlement_page_size) => (
+                2,
+                (settlement_page_size, 0, 0),
+                ([0; 32], U256::from(0), no_policy),
+            ),
+            GovernanceAction::LateSwingRule(window, extension, max_extensions) => (
+                3,
+                (window, extension, max_extensions),
+                ([0; 32], U256::from(0), no_policy),
+            ),
+            GovernanceAction::VotePolicy(proposal_type, min_cost, vote_policy) => (
+                4,
+                (proposal_type as u64, 0, 0),
+                ([0; 32], min_cost, vote_policy),
+            ),
+            GovernanceAction::VotingEngineAddress(voting_engine_address) => (
+                5,
+                (0, 0, 0),
+                (voting_engine_address.value(), U256::from(0), no_policy),
+            ),
+            GovernanceAction::VotingEngineContractHash(contract_hash) => (
+                6,
+                (0, 0, 0),
+                (contract_hash.value(), U256::from(0), no_policy),
+            ),
+            GovernanceAction::ReputationContractHash(contract_hash) => (
+                7,
+                (0, 0, 0),
+                (contract_hash.value(), U256::from(0), no_policy),
+            ),
+            GovernanceAction::ExecutionContractHash(contract_hash) => (
+                8,
+                (0, 0, 0),
+                (contract_hash.value(), U256::from(0), no_policy),
+            ),
+            GovernanceAction::Pause(operation_group, duration) => (
+                9,
+                (operation_group as u64, duration, 0),
+                ([0; 32], U256::from(0), no_policy),
+            ),
+            GovernanceAction::Unpause(operation_group) => (
+                10,
+                (operation_group as u64, 0, 0),
+                ([0; 32], U256::from(0), no_policy),
+            ),
+            GovernanceAction::GrantRole(role, account) => (
+                11,
+                (role as u64, 0, 0),
+                (account.value(), U256::from(0), no_policy),
+            ),
+            GovernanceAction::RevokeRole(role, account) => (
+                12,
+                (role as u64, 0, 0),
+                (account.value(), U256::from(0), no_policy),
+            ),
+        };
+        (kind, values, (hash, amount, vote_policy.serialize()))
+    }
+
+    pub fn deserialize(
+        serialized_action: GovernanceActionSerialized,
+    ) -> Result<GovernanceAction, ProposalError> {
+        let (kind, values, (hash, amount, vote_policy)) = serialized_action;
+        match kind {
+            0 => Ok(GovernanceAction::PolicingRatio(values.0)),
+            1 => Ok(GovernanceAction::ReputationAllocationRatio(values.0)),
+            2 => Ok(GovernanceAction::SettlementPageSize(values.0)),
+            3 => Ok(GovernanceAction::LateSwingRule(
+                values.0, values.1, values.2,
+            )),
+            4 => {
+                if values.0 > ProposalType::MultiOption as u64 {
+                    return Err(ProposalError::UnknownGovernanceAction);
+                }
+                Ok(GovernanceAction::VotePolicy(
+                    (values.0 as u8).into(),
+                    amount,
+                    VotePolicy::deserialize(vote_policy),
+                ))
+            }
+            5 => Ok(GovernanceAction::VotingEngineAddress(AccountHash::new(
+                hash,
+            ))),
+            6 => Ok(GovernanceAction::VotingEngineContractHash(
+                ContractHash::new(hash),
+            )),
+            7 => Ok(GovernanceAction::ReputationContractHash(ContractHash::new(
+                hash,
+            ))),
+            8 => Ok(GovernanceAction::ExecutionContractHash(ContractHash::new(
+                hash,
+            ))),
+            9 | 10 => {
+                if values.0 >= NUMBER_OF_OPERATION_GROUPS as u64 {
+                    return Err(ProposalError::UnknownGovernanceAction);
+                }
+                let operation_group: OperationGroup = (values.0 as u8).into();
+                if kind == 9 {
+                    Ok(GovernanceAction::Pause(operation_group, values.1))
+                } else {
+                    Ok(GovernanceAction::Unpause(operation_group))
+                }
+            }
+            11 | 12 => {
+                if values.0 >= NUMBER_OF_ROLES as u64 {
+                    return Err(ProposalError::UnknownGovernanceAction);
+                }
+                let role: Role = (values.0 as u8).into();
+                if kind == 11 {
+                    Ok(GovernanceAction::GrantRole(role, AccountHash::new(hash)))
+                } else {
+                    Ok(GovernanceAction::RevokeRole(role, AccountHash::new(hash)))
+                }
+            }
+            _ => Err(ProposalError::UnknownGovernanceAction),
+        }
+    }
+}
+
+// Actions are applied all or nothing: returns the actions to apply in order, none if any of
+// them is invalid, and the result of each action
+pub fn plan_governance_actions(
+    serialized_actions: Vec<GovernanceActionSerialized>,
+) -> (Vec<GovernanceAction>, Vec<GovernanceActionResult>) {
+    let governance_actions: Vec<Result<GovernanceAction, ProposalError>> = serialized_actions
+        .into_iter()
+        .map(|serialized_action| {
+            let governance_action: GovernanceAction =
+                GovernanceAction::deserialize(serialized_action)?;
+            governance_action.validate()?;
+            Ok(governance_action)
+        })
+        .collect();
+    if governance_actions.iter().all(Result::is_ok) {
+        let results: Vec<GovernanceActionResult> = governance_actions
+            .iter()
+            .map(|_| GovernanceActionResult::Applied)
+            .collect();
+        return (
+            governance_actions
+                .into_iter()
+                .filter_map(Result::ok)
+                .collect(),
+            results,
+        );
+    }
+    let results: Vec<GovernanceActionResult> = governance_actions
+        .iter()
+        .map(|governance_action| match governance_action {
+            Ok(_) => GovernanceActionResult::NotApplied,
+            Err(_) => GovernanceActionResult::Invalid,
+        })
+        .collect();
+    (Vec::new(), results)
+}
+
+impl From<u8> for GovernanceActionResult {
+    fn from(orig: u8) -> Self {
+        match orig {
+            0x0 => GovernanceActionResult::Pending,
+            0x1 => GovernanceActionResult::Applied,
+            0x2 => GovernanceActionResult::Invalid,
+            0x3 => GovernanceActionResult::NotApplied,
+            _ => GovernanceActionResult::Pending,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::vote_counting::CountingRule;
+
+    const ALI: AccountHash = AccountHash::new([1u8; 32]);
+
+    fn vote_policy() -> VotePolicy {
+        VotePolicy {
+            min_member_quorum: 10,
+            min_reputation_quorum: 10,
+            min_threshold: 50,
+            min_duration: 10,
+            max_duration: 100,
+            min_balance_share: 1000,
+            max_balance_share: 10000,
+            min_total_stake_share: 0,
+            max_total_stake_share: 10000,
+            counting_rules: 1 << CountingRule::SimpleMajority as u8,
+            allow_secret_ballot: false,
+            min_reveal_timeout: 0,
+        }
+    }
+
+    #[test]
+    fn test_serialization() {
+        let governance_actions: Vec<GovernanceAction> = alloc::vec![
+            GovernanceAction::PolicingRatio(30),
+            GovernanceAction::ReputationAllocationRatio(MAX_REPUTATION_ALLOCATION_RATIO),
+            GovernanceAction::SettlementPageSize(20),
+            GovernanceAction::LateSwingRule(10, 5, 2),
+            GovernanceAction::VotePolicy(ProposalType::Grant, U256::from(1000), vote_policy()),
+            GovernanceAction::VotingEngineAddress(ALI),
+            GovernanceAction::VotingEngineContractHash(ContractHash::new([2u8; 32])),
+            GovernanceAction::ReputationContractHash(ContractHash::new([3u8; 32])),
+            GovernanceAction::ExecutionContractHash(ContractHash::new([4u8; 32])),
+            GovernanceAction::Pause(OperationGroup::Claims, 10),
+            GovernanceAction::Unpause(OperationGroup::Milestones),
+            GovernanceAction::GrantRole(Role::Reviewer, ALI),
+            GovernanceAction::RevokeRole(Role::Compliance, ALI),
+        ];
+        for governance_action in governance_actions {
+            assert_eq!(
+                GovernanceAction::deserialize(governance_action.serialize()),
+                Ok(governance_action)
+            );
+        }
+    }
+
+    #[test]
+    fn test_unknown_actions_are_rejected() {
+        let (_, values, parameters) = GovernanceAction::PolicingRatio(30).serialize();
+        assert_eq!(
+            GovernanceAction::deserialize((13, values, parameters)),
+            Err(ProposalError::UnknownGovernanceAction)
+        );
+        assert_eq!(
+            GovernanceAction::deserialize((
+                9,
+                (NUMBER_OF_OPERATION_GROUPS as u64, 10, 0),
+                parameters
+            )),
+            Err(ProposalError::UnknownGovernanceAction)
+        );
+        assert_eq!(
+            GovernanceAction::deserialize((11, (NUMBER_OF_ROLES as u64, 0, 0), parameters)),
+            Err(ProposalError::UnknownGovernanceAction)
+        );
+    }
+
+    #[test]
+    fn test_values_are_validated() {
+        assert_eq!(GovernanceAction::PolicingRatio(100).validate(), Ok(()));
+        let invalid_actions: Vec<GovernanceAction> = alloc::vec![
+            GovernanceAction::PolicingRatio(101),
+            GovernanceAction::ReputationAllocationRatio(MAX_REPUTATION_ALLOCATION_RATIO + 1),
+            GovernanceAction::SettlementPageSize(MAX_SETTLEMENT_PAGE_SIZE + 1),
+            GovernanceAction::LateSwingRule(10, 0, 2),
+            GovernanceAction::LateSwingRule(10, 5, MAX_LATE_SWING_EXTENSIONS + 1),
+            GovernanceAction::VotePolicy(
+                ProposalType::Grant,
+                U256::from(0),
+                VotePolicy {
+                    counting_rules: 0,
+                    ..vote_policy()
+                }
+            ),
+            GovernanceAction::VotePolicy(
+                ProposalType::Grant,
+                U256::from(0),
+                VotePolicy {
+                    min_duration: 101,
+                    ..vote_policy()
+                }
+            ),
+            GovernanceAction::VotePolicy(
+                ProposalType::Grant,
+                U256::from(0),
+                VotePolicy {
+                    max_balance_share: 999,
+                    ..vote_policy()
+                }
+            ),
+            GovernanceAction::VotePolicy(
+                ProposalType::Grant,
+                U256::from(0),
+                VotePolicy {
+                    max_total_stake_share: BASIS_POINTS + 1,
+                    ..vote_policy()
+                }
+            ),
+            GovernanceAction::VotingEngineAddress(AccountHash::new([0u8; 32])),
+            GovernanceAction::ExecutionContractHash(ContractHash::new([0u8; 32])),
+            GovernanceAction::Pause(OperationGroup::Voting, 0),
+            GovernanceAction::GrantRole(Role::Failsafe, AccountHash::new([0u8; 32])),
+        ];
+        for governance_action in invalid_actions {
+            assert_eq!(
+                governance_action.validate(),
+                Err(ProposalError::GovernanceValueOutOfRange)
+            );
+        }
+    }
+
+    #[test]
+    fn test_actions_are_planned_all_or_nothing() {
+        let (governance_actions, results) = plan_governance_actions(alloc::vec![
+            GovernanceAction::PolicingRatio(30).serialize(),
+            GovernanceAction::SettlementPageSize(20).serialize(),
+        ]);
+        assert_eq!(
+            governance_actions,
+            alloc::vec![
+                GovernanceAction::PolicingRatio(30),
+                GovernanceAction::SettlementPageSize(20)
+            ]
+        );
+        assert_eq!(
+            results,
+            alloc::vec![
+                GovernanceActionResult::Applied,
+                GovernanceActionResult::Applied
+            ]
+        );
+
+        let (governance_actions, results) = plan_governance_actions(alloc::vec![
+            GovernanceAction::PolicingRatio(30).serialize(),
+            GovernanceAction::PolicingRatio(101).serialize(),
+            GovernanceAction::SettlementPageSize(20).serialize(),
+        ]);
+        assert_eq!(governance_actions, Vec::new());
+        assert_eq!(
+            results,
+            alloc::vec![
+                GovernanceActionResult::NotApplied,
+                GovernanceActionResult::Invalid,
+                GovernanceActionResult::NotApplied
+            ]
+        );
+    }
+}
@@ -0,0 +1,152 @@
+extern crate alloc;
+use crate::{custom_types::custom_types::GovernanceExecutionSerialized, error::*};
+use core::cmp::{Eq, Ord, PartialEq, PartialOrd};
+use types::account::AccountHash;
+
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone, Copy)]
+pub enum ExecutionStatus {
+    AwaitingApproval,
+    Queued,
+    Executed,
+    Vetoed,
+}
+
+// Timelock of a governance proposal: once approved, its actions wait `execution_delay` before
+// anyone can execute them, the failsafe and compliance accounts can veto them meanwhile
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone, Copy)]
+pub struct GovernanceExecution {
+    pub execution_delay: u64,
+    pub earliest_execution_at: u64,
+    pub status: ExecutionStatus,
+    // Who vetoed the actions, and when
+    pub veto: Option<(AccountHash, u64)>,
+}
+
+impl GovernanceExecution {
+    pub fn new(execution_delay: u64) -> GovernanceExecution {
+        GovernanceExecution {
+            execution_delay,
+            earliest_execution_at: 0,
+            status: ExecutionStatus::AwaitingApproval,
+            veto: None,
+        }
+    }
+
+    pub fn queue(&mut self, current_time: u64) {
+        self.status = ExecutionStatus::Queued;
+        self.earliest_execution_at = current_time + self.execution_delay;
+    }
+
+    pub fn execute(&mut self, current_time: u64) -> Result<(), VotingEngineError> {
+        if self.status != ExecutionStatus::Queued {
+            return Err(VotingEngineError::ExecutionNotQueued);
+        }
+        if current_time < self.earliest_execution_at {
+            return Err(VotingEngineError::TimelockNotExpired);
+        }
+        self.status = ExecutionStatus::Executed;
+        Ok(())
+    }
+
+    pub fn veto(
+        &mut self,
+        vetoer: AccountHash,
+        current_time: u64,
+    ) -> Result<(), VotingEngineError> {
+        if self.status != ExecutionStatus::Queued {
+            return Err(VotingEngineError::ExecutionNotQueued);
+        }
+        if current_time >= self.earliest_execution_at {
+            return Err(VotingEngineError::VetoWindowClosed);
+        }
+        self.status = ExecutionStatus::Vetoed;
+        self.veto = Some((vetoer, current_time));
+        Ok(())
+    }
+
+    pub fn serialize(&self) -> GovernanceExecutionSerialized {
+        (
+            (self.execution_delay, self.earliest_execution_at),
+            self.status as u8,
+            self.veto
+                .map(|(vetoer, vetoed_at)| (vetoer.value(), vetoed_at)),
+        )
+    }
+
+    pub fn deserialize(serialized_execution: GovernanceExecutionSerialized) -> GovernanceExecution {
+        GovernanceExecution {
+            execution_delay: serialized_execution.0 .0,
+            earliest_execution_at: serialized_execution.0 .1,
+            status: serialized_execution.1.into(),
+            veto: serialized_execution
+                .2
+                .map(|(vetoer, vetoed_at)| (AccountHash::new(vetoer), vetoed_at)),
+        }
+    }
+}
+
+impl From<u8> for ExecutionStatus {
+    fn from(orig: u8) -> Self {
+        match orig {
+            0x0 => ExecutionStatus::AwaitingApproval,
+            0x1 => ExecutionStatus::Queued,
+            0x2 => ExecutionStatus::Executed,
+            0x3 => ExecutionStatus::Vetoed,
+            _ => ExecutionStatus::AwaitingApproval,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ALI: AccountHash = AccountHash::new([1u8; 32]);
+
+    #[test]
+    fn test_actions_wait_for_the_timelock() {
+        let mut governance_execution: GovernanceExecution = GovernanceExecution::new(10);
+        assert_eq!(
+            governance_execution.execute(100),
+            Err(VotingEngineError::ExecutionNotQueued)
+        );
+        governance_execution.queue(100);
+        assert_eq!(governance_execution.earliest_execution_at, 110);
+        assert_eq!(
+            governance_execution.execute(109),
+            Err(VotingEngineError::TimelockNotExpired)
+        );
+        assert_eq!(governance_execution.execute(110), Ok(()));
+        assert_eq!(governance_execution.status, ExecutionStatus::Executed);
+        assert_eq!(
+            governance_execution.execute(111),
+            Err(VotingEngineError::ExecutionNotQueued)
+        );
+        assert_eq!(
+            governance_execution.veto(ALI, 105),
+            Err(VotingEngineError::ExecutionNotQueued)
+        );
+    }
+
+    #[test]
+    fn test_veto_is_recorded_within_the_window() {
+        let mut governance_execution: GovernanceExecution = GovernanceExecution::new(10);
+        governance_execution.queue(100);
+        let mut late_veto: GovernanceExecution = governance_execution;
+        assert_eq!(
+            late_veto.veto(ALI, 110),
+            Err(VotingEngineError::VetoWindowClosed)
+        );
+        assert_eq!(governance_execution.veto(ALI, 105), Ok(()));
+        assert_eq!(governance_execution.status, ExecutionStatus::Vetoed);
+        assert_eq!(governance_execution.veto, Some((ALI, 105)));
+        assert_eq!(
+            governance_execution.execute(110),
+            Err(VotingEngineError::ExecutionNotQueued)
+        );
+        assert_eq!(
+            GovernanceExecution::deserialize(governance_execution.serialize()),
+            governance_execution
+        );
+    }
+}
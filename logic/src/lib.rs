@@ -3,15 +3,44 @@
 extern crate alloc;
 
 pub mod custom_types;
+mod delegation;
 mod error;
 mod execution;
+mod fixed_point;
+mod governance_action;
+mod governance_execution;
+mod multi_option_voting;
+mod pause;
 mod proposal;
+mod reputation_lock;
+mod roles;
+mod simulation;
+mod vote_counting;
+mod vote_outcome;
+mod vote_policy;
 mod voting;
 
 pub use error::{ProposalError, VotingEngineError};
 
 pub use {
+    delegation::Delegation,
     execution::Project,
-    proposal::{GovernanceProposal, GovernanceVoteConfiguration, Proposal, ProposalType},
-    voting::{VoteResult, Voting},
+    governance_action::{plan_governance_actions, GovernanceAction, GovernanceActionResult},
+    governance_execution::{ExecutionStatus, GovernanceExecution},
+    multi_option_voting::{MultiOptionBallot, MultiOptionMethod, MultiOptionVoting},
+    pause::{OperationGroup, PauseState, NUMBER_OF_OPERATION_GROUPS},
+    proposal::{
+        GovernanceProposal, GovernanceVoteConfiguration, Proposal, ProposalType,
+        SecretBallotConfiguration, StakingLimits, UnrevealedPolicy,
+    },
+    reputation_lock::{ReputationLocks, VoteType},
+    roles::{Role, RoleRegistry, NUMBER_OF_ROLES},
+    simulation::simulate_vote,
+    vote_counting::{
+        CountingRule, OneMemberOneVote, QuadraticWeighting, SimpleMajority, Supermajority,
+        VoteCountingRule,
+    },
+    vote_outcome::VoteOutcomeReport,
+    vote_policy::{VotePolicies, VotePolicy},
+    voting::{commitment_hash, LateSwingRule, VoteDirection, VoteResult, Voting, VotingData},
 };
@@ -0,0 +1,650 @@
+extern crate alloc;
+use crate::{
+    custom_types::custom_types::{MultiOptionBallotsSerialized, MultiOptionVotingSerialized},
+    error::*,
+    fixed_point::{percentage_of_rounded_up, pro_rata},
+    vote_policy::VotePolicy,
+    voting::VoteResult,
+};
+use alloc::collections::{BTreeMap, BTreeSet};
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::cmp::{Eq, Ord, PartialEq, PartialOrd};
+use types::{account::AccountHash, U256};
+
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone, Copy)]
+pub enum MultiOptionMethod {
+    // Voters back every option they approve of with their whole stake
+    Approval,
+    // Instant-runoff: voters rank options, the weakest option is dropped until one holds
+    // a majority of the stake still in play
+    RankedChoice,
+}
+
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone)]
+pub struct MultiOptionBallot {
+    pub reputation_staked: U256,
+    // Approved options, or options by order of preference
+    pub choices: Vec<u8>,
+    pub claimed: bool,
+}
+
+// Picks one winner among several competing options, quorums are percentages of the members
+// and reputation eligible when the vote starts, balances are snapshotted then too
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone)]
+pub struct MultiOptionVoting {
+    pub start_timestamp: u64,
+    pub end_timestamp: u64,
+    pub method: MultiOptionMethod,
+    pub options: Vec<String>,
+    pub member_quorum: u64,
+    pub reputation_quorum: u64,
+    pub eligible_members: u64,
+    pub eligible_reputation: U256,
+    pub total_members: u64,
+    pub total_staked_reputation: U256,
+    pub ballots: BTreeMap<AccountHash, MultiOptionBallot>,
+    pub result: VoteResult,
+    pub winning_option: Option<u8>,
+    // Options dropped by the instant-runoff rounds
+    pub eliminated_options: Vec<u8>,
+    // Final round tally per option
+    pub option_votes: Vec<U256>,
+    pub winning_stake: U256,
+    pub losing_stake: U256,
+    // Losing stake the voting engine could not debit into the reward pool
+    pub uncollected_stakes: U256,
+    pub distributed_rewards: U256,
+    pub dust_collected: bool,
+}
+
+impl MultiOptionVoting {
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        start_timestamp: u64,
+        end_timestamp: u64,
+        method: MultiOptionMethod,
+        options: Vec<String>,
+        member_quorum: u64,
+        reputation_quorum: u64,
+        vote_policy: Option<VotePolicy>,
+        eligible_members: u64,
+        eligible_reputation: U256,
+    ) -> Result<MultiOptionVoting, VotingEngineError> {
+        if options.len() < 2 || options.len() > u8::MAX as usize {
+            return Err(VotingEngineError::InvalidOptions);
+        }
+        // The winner is picked by plurality or majority, the policy threshold does not apply
+        let vote_policy: VotePolicy = vote_policy.ok_or(VotingEngineError::NoVotePolicy)?;
+        if vote_policy.check_member_quorum(member_quorum).is_err()
+            || vote_policy
+                .check_reputation_quorum(reputation_quorum)
+                .is_err()
+            || vote_policy
+                .check_duration(end_timestamp.saturating_sub(start_timestamp))
+                .is_err()
+        {
+            return Err(VotingEngineError::OutsideVotePolicy);
+        }
+        Ok(MultiOptionVoting {
+            start_timestamp,
+            end_timestamp,
+            method,
+            option_votes: options.iter().map(|_| U256::from(0)).collect(),
+            options,
+            member_quorum,
+            reputation_quorum,
+            eligible_members,
+            eligible_reputation,
+            total_members: 0,
+            total_staked_reputation: U256::from(0),
+            ballots: BTreeMap::new(),
+            result: VoteResult::InVote,
+            winning_option: None,
+            eliminated_options: Vec::new(),
+            winning_stake: U256::from(0),
+            losing_stake: U256::from(0),
+            uncollected_stakes: U256::from(0),
+            distributed_rewards: U256::from(0),
+            dust_collected: false,
+        })
+    }
+
+    pub fn start_at(&self) -> u64 {
+        self.start_timestamp
+    }
+
+    pub fn end_at(&self) -> u64 {
+        self.end_timestamp
+    }
+
+    pub fn cast_vote(
+        &mut self,
+        caller: AccountHash,
+        current_time: u64,
+        reputation_balance: U256,
+        reputation_to_stake: U256,
+        committed_reputation: U256,
+        choices: Vec<u8>,
+    ) -> Result<U256, VotingEngineError> {
+        if self.ballots.contains_key(&caller) {
+            return Err(VotingEngineError::AlreadyVoted);
+        }
+        if current_time > self.end_at() {
+            return Err(VotingEngineError::VotingEnded);
+        }
+        if self.result != VoteResult::InVote {
+            return Err(VotingEngineError::VotingNotOngoing);
+        }
+        let mut distinct_choices: BTreeSet<u8> = BTreeSet::new();
+        for choice in choices.iter() {
+            if *choice as usize >= self.options.len() || !distinct_choices.insert(*choice) {
+                return Err(VotingEngineError::InvalidChoices);
+            }
+        }
+        if distinct_choices.is_empty() {
+            return Err(VotingEngineError::InvalidChoices);
+        }
+        if reputation_to_stake > reputation_balance.saturating_sub(committed_reputation) {
+            return Err(VotingEngineError::InvalidReputationToStake);
+        }
+        self.ballots.insert(
+            caller,
+            MultiOptionBallot {
+                reputation_staked: reputation_to_stake,
+                choices,
+                claimed: false,
+            },
+        );
+        self.total_members += 1;
+        self.total_staked_reputation += reputation_to_stake;
+        Ok(reputation_to_stake)
+    }
+
+    pub fn calculate_vote_outcome(
+        &mut self,
+        current_time: u64,
+    ) -> Result<VoteResult, VotingEngineError> {
+        if self.end_at() > current_time {
+            return Err(VotingEngineError::VotingOngoing);
+        }
+        if self.result != VoteResult::InVote {
+            return Err(VotingEngineError::VotingNotOngoing);
+        }
+        if U256::from(self.total_members)
+            < percentage_of_rounded_up(U256::from(self.eligible_members), self.member_quorum)
+        {
+            self.result = VoteResult::MemberQuorumUnmet;
+            return Ok(self.result);
+        }
+        if self.total_staked_reputation
+            < percentage_of_rounded_up(self.eligible_reputation, self.reputation_quorum)
+        {
+            self.result = VoteResult::ReputationQuorumUnmet;
+            return Ok(self.result);
+        }
+        self.winning_option = match self.method {
+            MultiOptionMethod::Approval => self.approval_winner(),
+            MultiOptionMethod::RankedChoice => self.ranked_choice_winner(),
+        };
+        match self.winning_option {
+            Some(winning_option) => {
+                let mut winning_stake: U256 = U256::from(0);
+                for ballot in self.ballots.values() {
+                    if self.supports(ballot, winning_option) {
+                        winning_stake += ballot.reputation_staked;
+                    }
+                }
+                self.winning_stake = winning_stake;
+                self.losing_stake = self.total_staked_reputation - self.winning_stake;
+                self.result = VoteResult::Approved;
+            }
+            // Tied options
+            None => self.result = VoteResult::FailCriteriaUnmet,
+        }
+        Ok(self.result)
+    }
+
+    // Voters backing the winning option share the stake of the other ballots pro rata, stakes
+    // are only locked so a claim returns the reward alone, nothing when no option won
+    pub fn claim_reputation(&mut self, caller: AccountHash) -> Result<U256, VotingEngineError> {
+        if self.result == VoteResult::InVote {
+            return Err(VotingEngineError::VotingOngoing);
+        }
+        let ballot: MultiOptionBallot = match self.ballots.get(&caller) {
+            Some(ballot) => ballot.clone(),
+            None => return Err(VotingEngineError::NoReputationToClaim),
+        };
+        if ballot.claimed {
+            return Err(VotingEngineError::ReputationAlreadyClaimed);
+        }
+        let rep_gained: U256 = match self.winning_option {
+            Some(winning_option) if self.supports(&ballot, winning_option) => pro_rata(
+                self.losing_stake.saturating_sub(self.uncollected_stakes),
+                ballot.reputation_staked,
+                self.winning_stake,
+            ),
+            _ => U256::from(0),
+        };
+        self.distributed_rewards += rep_gained;
+        self.ballots.get_mut(&caller).unwrap().claimed = true;
+        Ok(rep_gained)
+    }
+
+    // Once every ballot backing the winner claimed, the pool leftovers from rounding down the
+    // rewards go to the DAO, only once
+    pub fn take_rounding_dust(&mut self) -> U256 {
+        let winning_option: u8 = match self.winning_option {
+            Some(winning_option) => winning_option,
+            None => return U256::from(0),
+        };
+        if self.dust_collected
+            || self
+                .ballots
+                .values()
+                .any(|ballot| self.supports(ballot, winning_option) && !ballot.claimed)
+        {
+            return U256::from(0);
+        }
+        self.dust_collected = true;
+        self.losing_stake
+            .saturating_sub(self.uncollected_stakes + self.distributed_rewards)
+    }
+
+    // The voting engine debits losing stakes into the reward pool when the vote closes, a
+    // voter's balance may have fallen below the stake in the meantime
+    pub fn record_uncollected_stake(&mut self, uncollected_stake: U256) {
+        self.uncollected_stakes += uncollected_stake;
+    }
+
+    // Voters whose ballot does not back the winning option, with their stake
+    pub fn losing_voters(&self) -> Vec<(AccountHash, U256)> {
+        let winning_option: u8 = match self.winning_option {
+            Some(winning_option) => winning_option,
+            None => return Vec::new(),
+        };
+        self.ballots
+            .iter()
+            .filter(|(_, ballot)| !self.supports(ballot, winning_option))
+            .map(|(voter, ballot)| (*voter, ballot.reputation_staked))
+            .collect()
+    }
+
+    // Approval ballots back the winner when they approve it, ranked ballots when the
+    // winner is their preferred option left after the eliminations
+    fn supports(&self, ballot: &MultiOptionBallot, option: u8) -> bool {
+        match self.method {
+            MultiOptionMethod::Approval => ballot.choices.contains(&option),
+            MultiOptionMethod::RankedChoice => self.preferred_option(ballot) == Some(option),
+        }
+    }
+
+    fn preferred_option(&self, ballot: &MultiOptionBallot) -> Option<u8> {
+        ballot
+            .choices
+            .iter()
+            .find(|choice| !self.eliminated_options.contains(*choice))
+            .copied()
+    }
+
+    fn approval_winner(&mut self) -> Option<u8> {
+        let mut option_votes: Vec<U256> = self.options.iter().map(|_| U256::from(0)).collect();
+        for ballot in self.ballots.values() {
+            for choice in ballot.choices.iter() {
+                option_votes[*choice as usize] += ballot.reputation_staked;
+            }
+        }
+        self.option_votes = option_votes;
+        self.leading_option()
+    }
+
+    fn ranked_choice_winner(&mut self) -> Option<u8> {
+        loop {
+            let mut option_votes: Vec<U256> = self.options.iter().map(|_| U256::from(0)).collect();
+            let mut active_stake: U256 = U256::from(0);
+            for ballot in self.ballots.values() {
+                // Exhausted ballots drop out of the count
+                if let Some(preferred_option) = self.preferred_option(ballot) {
+                    option_votes[preferred_option as usize] += ballot.reputation_staked;
+                    active_stake += ballot.reputation_staked;
+                }
+            }
+            self.option_votes = option_votes;
+            let remaining_options: Vec<u8> = (0..self.options.len() as u8)
+                .filter(|option| !self.eliminated_options.contains(option))
+                .collect();
+            if active_stake == U256::from(0) {
+                return None;
+            }
+            for option in remaining_options.iter() {
+                if self.option_votes[*option as usize] * 2 > active_stake {
+                    return Some(*option);
+                }
+            }
+            if remaining_options.len() <= 2 {
+                // No majority between the last two options
+                return self.leading_option();
+            }
+            // Drop the weakest option, the latest listed one on ties
+            let weakest_option: u8 = *remaining_options
+                .iter()
+                .min_by(|first, second| {
+                    self.option_votes[**first as usize]
+                        .cmp(&self.option_votes[**second as usize])
+                        .then(second.cmp(first))
+                })
+                .unwrap();
+            self.eliminated_options.push(weakest_option);
+        }
+    }
+
+    // Option with the most votes, None on a tie
+    fn leading_option(&self) -> Option<u8> {
+        let mut leading_option: Option<u8> = None;
+        let mut leading_votes: U256 = U256::from(0);
+        let mut tied: bool = false;
+        for (option, votes) in self.option_votes.iter().enumerate() {
+            if self.eliminated_options.contains(&(option as u8)) {
+                continue;
+            }
+            if leading_option.is_none() || *votes > leading_votes {
+                leading_option = Some(option as u8);
+                leading_votes = *votes;
+                tied = false;
+            } else if *votes == leading_votes {
+                tied = true;
+            }
+        }
+        if tied || leading_votes == U256::from(0) {
+            return None;
+        }
+        leading_option
+    }
+
+    pub fn serialize(&self) -> MultiOptionVotingSerialized {
+        let mut ballots_output: MultiOptionBallotsSerialized = BTreeMap::new();
+        for (key, ballot) in self.ballots.iter() {
+            ballots_output.insert(
+                key.value(),
+                (
+                    ballot.reputation_staked,
+                    ballot.choices.clone(),
+                    ballot.claimed,
+                ),
+            );
+        }
+        (
+            (
+                (self.start_timestamp, self.end_timestamp, self.method as u8),
+                (
+                    self.options.clone(),
+                    self.member_quorum,
+                    self.reputation_quorum,
+                ),
+                (self.eligible_members, self.eligible_reputation),
+            ),
+            (
+                (
+                    self.total_members,
+                    self.total_staked_reputation,
+                    self.winning_option,
+                ),
+                (
+                    self.result as u8,
+                    self.eliminated_options.clone(),
+                    (self.winning_stake, self.losing_stake),
+                ),
+                (ballots_output, self.option_votes.clone()),
+            ),
+            (
+                self.uncollected_stakes,
+                self.distributed_rewards,
+                self.dust_collected,
+            ),
+        )
+    }
+
+    pub fn deserialize(serialized_voting: MultiOptionVotingSerialized) -> MultiOptionVoting {
+        let mut ballots: BTreeMap<AccountHash, MultiOptionBallot> = BTreeMap::new();
+        for (key, ballot) in serialized_voting.1 .2 .0 {
+            ballots.insert(
+                AccountHash::new(key),
+                MultiOptionBallot {
+                    reputation_staked: ballot.0,
+                    choices: ballot.1,
+                    claimed: ballot.2,
+                },
+            );
+        }
+        MultiOptionVoting {
+            start_timestamp: serialized_voting.0 .0 .0,
+            end_timestamp: serialized_voting.0 .0 .1,
+            method: serialized_voting.0 .0 .2.into(),
+            options: serialized_voting.0 .1 .0,
+            member_quorum: serialized_voting.0 .1 .1,
+            reputation_quorum: serialized_voting.0 .1 .2,
+            eligible_members: serialized_voting.0 .2 .0,
+            eligible_reputation: serialized_voting.0 .2 .1,
+            total_members: serialized_voting.1 .0 .0,
+            total_staked_reputation: serialized_voting.1 .0 .1,
+            winning_option: serialized_voting.1 .0 .2,
+            result: serialized_voting.1 .1 .0.into(),
+            eliminated_options: serialized_voting.1 .1 .1,
+            winning_stake: serialized_voting.1 .1 .2 .0,
+            losing_stake: serialized_voting.1 .1 .2 .1,
+            ballots,
+            option_votes: serialized_voting.1 .2 .1,
+            uncollected_stakes: serialized_voting.2 .0,
+            distributed_rewards: serialized_voting.2 .1,
+            dust_collected: serialized_voting.2 .2,
+        }
+    }
+}
+
+impl From<u8> for MultiOptionMethod {
+    fn from(orig: u8) -> Self {
+        match orig {
+            0 => MultiOptionMethod::Approval,
+            1 => MultiOptionMethod::RankedChoice,
+            _ => MultiOptionMethod::Approval,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::string::ToString;
+
+    const ALI: AccountHash = AccountHash::new([1u8; 32]);
+    const BOB: AccountHash = AccountHash::new([2u8; 32]);
+    const JON: AccountHash = AccountHash::new([3u8; 32]);
+    const END: u64 = 100;
+
+    fn policy(min_member_quorum: u64) -> VotePolicy {
+        VotePolicy {
+            min_member_quorum,
+            min_reputation_quorum: 0,
+            min_threshold: 0,
+            min_duration: 10,
+            max_duration: 1000,
+            min_balance_share: 0,
+            max_balance_share: 10000,
+            min_total_stake_share: 0,
+            max_total_stake_share: 10000,
+            counting_rules: 0b1111,
+            allow_secret_ballot: false,
+            min_reveal_timeout: 0,
+        }
+    }
+
+    // Three options, five members holding 500 reputation at the start of the vote
+    fn multi_option_vote(method: MultiOptionMethod, member_quorum: u64) -> MultiOptionVoting {
+        MultiOptionVoting::new(
+            0,
+            END,
+            method,
+            alloc::vec!["a".to_string(), "b".to_string(), "c".to_string()],
+            member_quorum,
+            0,
+            Some(policy(0)),
+            5,
+            U256::from(500),
+        )
+        .unwrap()
+    }
+
+    fn cast(voting: &mut MultiOptionVoting, voter: AccountHash, stake: u64, choices: Vec<u8>) {
+        voting
+            .cast_vote(
+                voter,
+                10,
+                U256::from(100),
+                U256::from(stake),
+                U256::from(0),
+                choices,
+            )
+            .unwrap();
+    }
+
+    #[test]
+    fn test_vote_is_bounded_by_its_policy() {
+        let options: Vec<String> = alloc::vec!["a".to_string(), "b".to_string()];
+        let new_vote = |member_quorum: u64, vote_policy: Option<VotePolicy>| {
+            MultiOptionVoting::new(
+                0,
+                END,
+                MultiOptionMethod::Approval,
+                options.clone(),
+                member_quorum,
+                0,
+                vote_policy,
+                5,
+                U256::from(500),
+            )
+        };
+        assert_eq!(
+            new_vote(0, None).map(|_| ()),
+            Err(VotingEngineError::NoVotePolicy)
+        );
+        assert_eq!(
+            new_vote(10, Some(policy(20))).map(|_| ()),
+            Err(VotingEngineError::OutsideVotePolicy)
+        );
+        assert!(new_vote(20, Some(policy(20))).is_ok());
+    }
+
+    #[test]
+    fn test_choices_are_validated() {
+        let mut voting = multi_option_vote(MultiOptionMethod::RankedChoice, 0);
+        let mut cast_choices = |choices: Vec<u8>| {
+            voting.cast_vote(
+                ALI,
+                10,
+                U256::from(100),
+                U256::from(10),
+                U256::from(0),
+                choices,
+            )
+        };
+        assert_eq!(
+            cast_choices(alloc::vec![3]),
+            Err(VotingEngineError::InvalidChoices)
+        );
+        assert_eq!(
+            cast_choices(alloc::vec![1, 1]),
+            Err(VotingEngineError::InvalidChoices)
+        );
+        assert_eq!(
+            cast_choices(Vec::new()),
+            Err(VotingEngineError::InvalidChoices)
+        );
+        assert_eq!(cast_choices(alloc::vec![1, 0]), Ok(U256::from(10)));
+    }
+
+    #[test]
+    fn test_ranked_choice_eliminates_the_weakest_option() {
+        let mut voting = multi_option_vote(MultiOptionMethod::RankedChoice, 0);
+        cast(&mut voting, ALI, 40, alloc::vec![0]);
+        cast(&mut voting, BOB, 35, alloc::vec![1, 2]);
+        cast(&mut voting, JON, 25, alloc::vec![2, 1]);
+        assert_eq!(
+            voting.calculate_vote_outcome(END - 1),
+            Err(VotingEngineError::VotingOngoing)
+        );
+        assert_eq!(voting.calculate_vote_outcome(END), Ok(VoteResult::Approved));
+        // No majority in the first round, JON's ballot moves to b once c is dropped
+        assert_eq!(voting.eliminated_options, alloc::vec![2]);
+        assert_eq!(voting.winning_option, Some(1));
+        assert_eq!(
+            voting.option_votes,
+            alloc::vec![U256::from(40), U256::from(60), U256::from(0)]
+        );
+        assert_eq!(voting.winning_stake, U256::from(60));
+        assert_eq!(voting.losing_stake, U256::from(40));
+        assert_eq!(voting.losing_voters(), alloc::vec![(ALI, U256::from(40))]);
+    }
+
+    #[test]
+    fn test_rewards_are_paid_from_the_losing_stake() {
+        let mut voting = multi_option_vote(MultiOptionMethod::RankedChoice, 0);
+        cast(&mut voting, ALI, 40, alloc::vec![0]);
+        cast(&mut voting, BOB, 35, alloc::vec![1, 2]);
+        cast(&mut voting, JON, 25, alloc::vec![2, 1]);
+        voting.calculate_vote_outcome(END).unwrap();
+
+        // Claims return the reward only, rounded down
+        assert_eq!(voting.claim_reputation(ALI), Ok(U256::from(0)));
+        assert_eq!(voting.claim_reputation(BOB), Ok(U256::from(23)));
+        assert_eq!(voting.take_rounding_dust(), U256::from(0));
+        assert_eq!(voting.claim_reputation(JON), Ok(U256::from(16)));
+        assert_eq!(
+            voting.claim_reputation(JON),
+            Err(VotingEngineError::ReputationAlreadyClaimed)
+        );
+        assert_eq!(voting.take_rounding_dust(), U256::from(1));
+        assert_eq!(voting.take_rounding_dust(), U256::from(0));
+    }
+
+    #[test]
+    fn test_uncollected_stake_is_left_out_of_the_rewards() {
+        let mut voting = multi_option_vote(MultiOptionMethod::Approval, 0);
+        cast(&mut voting, ALI, 40, alloc::vec![0]);
+        cast(&mut voting, BOB, 60, alloc::vec![1]);
+        voting.calculate_vote_outcome(END).unwrap();
+        assert_eq!(voting.winning_option, Some(1));
+        // ALI only held 30 of the stake when it was debited
+        voting.record_uncollected_stake(U256::from(10));
+        assert_eq!(voting.claim_reputation(BOB), Ok(U256::from(30)));
+        assert_eq!(voting.take_rounding_dust(), U256::from(0));
+    }
+
+    #[test]
+    fn test_tied_and_quorumless_votes_pay_nothing() {
+        let mut voting = multi_option_vote(MultiOptionMethod::Approval, 0);
+        cast(&mut voting, ALI, 40, alloc::vec![0, 2]);
+        cast(&mut voting, BOB, 40, alloc::vec![1, 2]);
+        voting.calculate_vote_outcome(END).unwrap();
+        // a and b tie below c, which both ballots approve
+        assert_eq!(voting.winning_option, Some(2));
+
+        let mut voting = multi_option_vote(MultiOptionMethod::Approval, 0);
+        cast(&mut voting, ALI, 40, alloc::vec![0]);
+        cast(&mut voting, BOB, 40, alloc::vec![1]);
+        assert_eq!(
+            voting.calculate_vote_outcome(END),
+            Ok(VoteResult::FailCriteriaUnmet)
+        );
+        assert_eq!(voting.claim_reputation(ALI), Ok(U256::from(0)));
+        assert_eq!(voting.losing_voters(), Vec::new());
+
+        // 60% of the five members is three ballots
+        let mut voting = multi_option_vote(MultiOptionMethod::Approval, 60);
+        cast(&mut voting, ALI, 40, alloc::vec![0]);
+        cast(&mut voting, BOB, 40, alloc::vec![0]);
+        assert_eq!(
+            voting.calculate_vote_outcome(END),
+            Ok(VoteResult::MemberQuorumUnmet)
+        );
+    }
+}
@@ -0,0 +1,128 @@
+extern crate alloc;
+use crate::custom_types::custom_types::PauseStateSerialized;
+use alloc::collections::BTreeMap;
+use core::cmp::{max, Eq, Ord, PartialEq, PartialOrd};
+
+// 30 days, in the same unit as the block time
+pub const MAX_PAUSE_DURATION: u64 = 2_592_000_000;
+pub const NUMBER_OF_OPERATION_GROUPS: u8 = 5;
+
+// Entry points that can be paused together, read-only entry points are never paused
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone, Copy)]
+pub enum OperationGroup {
+    // New proposals and votes
+    Proposals,
+    // Casting, delegating and finalizing votes
+    Voting,
+    // Reputation claims and stake refunds
+    Claims,
+    // Project and milestone transitions
+    Milestones,
+    // Reputation transfers
+    Transfers,
+}
+
+// Every pause expires on its own, governance can lift it earlier
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone, Default)]
+pub struct PauseState {
+    pub paused_until: BTreeMap<OperationGroup, u64>,
+}
+
+impl PauseState {
+    pub fn new() -> PauseState {
+        PauseState::default()
+    }
+
+    pub fn is_valid_duration(duration: u64) -> bool {
+        duration > 0 && duration <= MAX_PAUSE_DURATION
+    }
+
+    pub fn is_paused(&self, operation_group: OperationGroup, current_time: u64) -> bool {
+        match self.paused_until.get(&operation_group) {
+            Some(paused_until) => current_time < *paused_until,
+            None => false,
+        }
+    }
+
+    // A pause never shortens a longer one already in place
+    pub fn pause(&mut self, operation_group: OperationGroup, current_time: u64, duration: u64) {
+        let paused_until: u64 = current_time + duration;
+        let current_pause: u64 = self
+            .paused_until
+            .get(&operation_group)
+            .copied()
+            .unwrap_or(0);
+        self.paused_until
+            .insert(operation_group, max(current_pause, paused_until));
+    }
+
+    pub fn unpause(&mut self, operation_group: OperationGroup) {
+        self.paused_until.remove(&operation_group);
+    }
+
+    pub fn serialize(&self) -> PauseStateSerialized {
+        let mut output: PauseStateSerialized = BTreeMap::new();
+        for (operation_group, paused_until) in self.paused_until.iter() {
+            output.insert(*operation_group as u8, *paused_until);
+        }
+        output
+    }
+
+    pub fn deserialize(serialized_pause_state: PauseStateSerialized) -> PauseState {
+        let mut paused_until: BTreeMap<OperationGroup, u64> = BTreeMap::new();
+        for (operation_group, group_paused_until) in serialized_pause_state {
+            paused_until.insert(operation_group.into(), group_paused_until);
+        }
+        PauseState { paused_until }
+    }
+}
+
+impl From<u8> for OperationGroup {
+    fn from(orig: u8) -> Self {
+        match orig {
+            0x0 => OperationGroup::Proposals,
+            0x1 => OperationGroup::Voting,
+            0x2 => OperationGroup::Claims,
+            0x3 => OperationGroup::Milestones,
+            0x4 => OperationGroup::Transfers,
+            _ => OperationGroup::Proposals,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pauses_expire_and_only_cover_their_group() {
+        let mut pause_state: PauseState = PauseState::new();
+        pause_state.pause(OperationGroup::Claims, 100, 10);
+        assert!(pause_state.is_paused(OperationGroup::Claims, 109));
+        assert!(!pause_state.is_paused(OperationGroup::Claims, 110));
+        assert!(!pause_state.is_paused(OperationGroup::Voting, 105));
+        pause_state.unpause(OperationGroup::Claims);
+        assert!(!pause_state.is_paused(OperationGroup::Claims, 105));
+    }
+
+    #[test]
+    fn test_a_pause_never_shortens_a_longer_one() {
+        let mut pause_state: PauseState = PauseState::new();
+        pause_state.pause(OperationGroup::Voting, 100, 50);
+        pause_state.pause(OperationGroup::Voting, 110, 10);
+        assert!(pause_state.is_paused(OperationGroup::Voting, 149));
+        pause_state.pause(OperationGroup::Voting, 140, 20);
+        assert!(pause_state.is_paused(OperationGroup::Voting, 159));
+        assert_eq!(
+            PauseState::deserialize(pause_state.serialize()),
+            pause_state
+        );
+    }
+
+    #[test]
+    fn test_pause_durations_are_bounded() {
+        assert!(!PauseState::is_valid_duration(0));
+        assert!(PauseState::is_valid_duration(MAX_PAUSE_DURATION));
+        assert!(!PauseState::is_valid_duration(MAX_PAUSE_DURATION + 1));
+    }
+}
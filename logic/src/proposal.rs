@@ -2,11 +2,15 @@
 extern crate alloc;
 use crate::{
     custom_types::custom_types::{
-        FundingTrancheSerialized, GovernanceProposalSerialized,
+        FundingTrancheSerialized, GovernanceActionSerialized, GovernanceProposalSerialized,
         GovernanceVoteConfigurationSerialized, MilestoneSerialized, ProposalSerialized,
-        RatiosSerialized, SponsorsSerialized, VoteConfigurationSerialized,
+        RatiosSerialized, SecretBallotConfigurationSerialized, SponsorsSerialized,
+        StakingLimitsSerialized, VoteConfigurationSerialized,
     },
     error::*,
+    governance_action::{GovernanceAction, MAX_GOVERNANCE_ACTIONS},
+    vote_counting::CountingRule,
+    vote_policy::VotePolicy,
 };
 use alloc::collections::BTreeMap;
 use alloc::string::String;
@@ -39,21 +43,52 @@ pub struct Ratios {
 }
 #[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone, Copy)]
 pub struct VoteConfiguration {
+    // Percentages of the members and reputation eligible at the vote snapshot
     pub member_quorum: u64,
-    pub reputation_quorum: U256,
+    pub reputation_quorum: u64,
     pub threshold: u8,
     // How long does the vote remain active
     pub timeout: u64,
-    pub voter_staking_limits: U256,
+    pub voter_staking_limits: StakingLimits,
+    pub secret_ballot: Option<SecretBallotConfiguration>,
+    pub counting_rule: CountingRule,
+}
+// How much a single voter may stake, in basis points (10000 is 100%)
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone, Copy)]
+pub struct StakingLimits {
+    // Share of the voter's reputation balance
+    pub balance_share: u64,
+    // Share of the vote's total stake, no cap when None
+    pub total_stake_share: Option<u64>,
+}
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone, Copy)]
+pub struct SecretBallotConfiguration {
+    // How long does the reveal window remain open once the commit phase ends
+    pub reveal_timeout: u64,
+    pub unrevealed_policy: UnrevealedPolicy,
+}
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone, Copy)]
+pub enum UnrevealedPolicy {
+    // Unrevealed reputation goes to the winning side
+    Forfeit,
+    // Unrevealed reputation is released back to the voter
+    Refund,
 }
 #[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone)]
 pub struct GovernanceVoteConfiguration {
-    pub transition_vote_quorum: U256,
+    // Quorums are percentages of the reputation eligible at the vote snapshot
+    pub transition_vote_quorum: u64,
     pub transition_vote_threshold: u64,
     pub proposal_repository_address: String,
-    pub full_vote_quorum: U256,
+    pub full_vote_quorum: u64,
     pub full_vote_threshold: u64,
+    // When does the transition vote end
     pub timeout: u64,
+    // How long does the full vote remain active once the transition vote passes
+    pub full_vote_timeout: u64,
+    pub voter_staking_limits: StakingLimits,
+    pub secret_ballot: Option<SecretBallotConfiguration>,
+    pub counting_rule: CountingRule,
 }
 #[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone)]
 pub struct Proposal {
@@ -62,6 +97,7 @@ pub struct Proposal {
     pub storage_fingerprint: String,
     pub proposal_type: ProposalType,
     pub proposer: AccountHash,
+    pub staked_rep: U256,
     pub citations: Vec<u64>,
     pub ratios: Ratios,
     pub vote_configuration: VoteConfiguration,
@@ -75,17 +111,21 @@ pub struct GovernanceProposal {
     pub name: String,
     pub repository_url: String,
     pub proposer: AccountHash,
+    pub staked_rep: U256,
     pub sponsors: BTreeMap<AccountHash, U256>,
     pub proposal_type: ProposalType,
     pub vote_configuration: GovernanceVoteConfiguration,
     pub proposal_status: ProposalStatus,
-    pub new_variable_key_value: (String, String),
+    // Applied in order, all or nothing
+    pub governance_actions: Vec<GovernanceAction>,
 }
 #[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone, Copy)]
 pub enum ProposalType {
     Grant,
     Governance,
     AnalysisAcceptance,
+    // Votes between competing options, only used to look up their vote policy
+    MultiOption,
 }
 #[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone, Copy)]
 pub enum ProposalStatus {
@@ -110,6 +150,8 @@ impl Proposal {
         reputation_balance: U256,
         sponsors: Vec<(AccountHash, U256)>,
         cost: U256,
+        vote_policy: Option<VotePolicy>,
+        current_time: u64,
     ) -> Result<Proposal, ProposalError> {
         let proposal_policing_ratio: u64 = ratios.0;
         // let proposal_citation_ratio: u8 = *ratios.get(1).unwrap();
@@ -165,10 +207,23 @@ impl Proposal {
             sponsors_mapping.insert(sponsor.0, sponsor.1);
         }
         let member_quorum: u64 = vote_configuration.0 .0;
-        let reputation_quorum: U256 = vote_configuration.0 .1;
+        let reputation_quorum: u64 = vote_configuration.0 .1;
+        let counting_rule: CountingRule = vote_configuration.0 .2.into();
         let threshold: u8 = vote_configuration.1 .0;
         let timeout: u64 = vote_configuration.1 .1;
-        let voter_staking_limits: U256 = vote_configuration.1 .2;
+        let voter_staking_limits: StakingLimits =
+            StakingLimits::deserialize(vote_configuration.1 .2);
+        let secret_ballot: Option<SecretBallotConfiguration> = vote_configuration
+            .2
+            .map(SecretBallotConfiguration::deserialize);
+        let vote_policy: VotePolicy = vote_policy.ok_or(ProposalError::NoVotePolicy)?;
+        vote_policy.check_member_quorum(member_quorum)?;
+        vote_policy.check_reputation_quorum(reputation_quorum)?;
+        vote_policy.check_threshold(threshold.into())?;
+        vote_policy.check_duration(timeout.saturating_sub(current_time))?;
+        vote_policy.check_staking_limits(voter_staking_limits)?;
+        vote_policy.check_counting_rule(counting_rule)?;
+        vote_policy.check_secret_ballot(secret_ballot)?;
         Ok(Proposal {
             name: name,
             storage_fingerprint: storage_fingerprint,
@@ -179,9 +234,12 @@ impl Proposal {
                 voter_staking_limits,
                 timeout,
                 threshold,
+                secret_ballot,
+                counting_rule,
             },
             proposal_type: new_category,
             proposer,
+            staked_rep,
             citations: citations,
             milestones: mstones,
             ratios: Ratios {
@@ -241,6 +299,7 @@ impl Proposal {
                 self.serialize_sponsors(),
                 self.cost,
             ),
+            self.staked_rep,
         )
     }
 
@@ -256,12 +315,16 @@ impl Proposal {
             (
                 self.vote_configuration.member_quorum,
                 self.vote_configuration.reputation_quorum,
+                self.vote_configuration.counting_rule as u8,
             ),
             (
                 self.vote_configuration.threshold,
                 self.vote_configuration.timeout,
-                self.vote_configuration.voter_staking_limits,
+                self.vote_configuration.voter_staking_limits.serialize(),
             ),
+            self.vote_configuration
+                .secret_ballot
+                .map(|secret_ballot| secret_ballot.serialize()),
         )
     }
     fn serialize_milestones(&self) -> BTreeMap<u64, MilestoneSerialized> {
@@ -327,21 +390,29 @@ impl Proposal {
             proposer: AccountHash::new(serialized_proposal.0 .1 .1),
             citations: serialized_proposal.0 .1 .2,
             ratios: Ratios {
-                policing_ratio: serialized_proposal.0 .2 .0.0,
-                op_ratio: serialized_proposal.0 .2 .0.1,
-                citation_ratio: serialized_proposal.0 .2 .0.2,
+                policing_ratio: serialized_proposal.0 .2 .0 .0,
+                op_ratio: serialized_proposal.0 .2 .0 .1,
+                citation_ratio: serialized_proposal.0 .2 .0 .2,
             },
             vote_configuration: VoteConfiguration {
                 member_quorum: serialized_proposal.0 .2 .1 .0 .0,
                 reputation_quorum: serialized_proposal.0 .2 .1 .0 .1,
                 threshold: serialized_proposal.0 .2 .1 .1 .0,
                 timeout: serialized_proposal.0 .2 .1 .1 .1,
-                voter_staking_limits: serialized_proposal.0 .2 .1 .1 .2,
+                voter_staking_limits: StakingLimits::deserialize(serialized_proposal.0 .2 .1 .1 .2),
+                secret_ballot: serialized_proposal
+                    .0
+                     .2
+                     .1
+                     .2
+                    .map(SecretBallotConfiguration::deserialize),
+                counting_rule: serialized_proposal.0 .2 .1 .0 .2.into(),
             },
             milestones: Self::deserialize_milestones(serialized_proposal.0 .2 .2),
             proposal_status: serialized_proposal.1 .0.into(),
             sponsors: Self::deserialize_sponsors(serialized_proposal.1 .1),
             cost: serialized_proposal.1 .2,
+            staked_rep: serialized_proposal.2,
         }
     }
 
@@ -399,11 +470,40 @@ impl GovernanceProposal {
         reputation_balance: U256,
         sponsors: Vec<(AccountHash, U256)>,
         repository_url: String,
-        new_variable_key_value: (String, String),
+        governance_actions: Vec<GovernanceActionSerialized>,
+        vote_policy: Option<VotePolicy>,
+        current_time: u64,
     ) -> Result<GovernanceProposal, ProposalError> {
         if staked_rep > reputation_balance {
             return Err(ProposalError::StakedRepGreaterThanReputationBalance);
         }
+        if governance_actions.is_empty() || governance_actions.len() > MAX_GOVERNANCE_ACTIONS {
+            return Err(ProposalError::InvalidNumberOfGovernanceActions);
+        }
+        // Every action is rejected before anyone stakes on it
+        let mut deserialized_governance_actions: Vec<GovernanceAction> = Vec::new();
+        for governance_action in governance_actions {
+            let governance_action: GovernanceAction =
+                GovernanceAction::deserialize(governance_action)?;
+            governance_action.validate()?;
+            deserialized_governance_actions.push(governance_action);
+        }
+        let vote_policy: VotePolicy = vote_policy.ok_or(ProposalError::NoVotePolicy)?;
+        // Both the transition vote and the full vote are held to the policy
+        vote_policy.check_reputation_quorum(vote_configuration.0 .0)?;
+        vote_policy.check_threshold(vote_configuration.0 .1)?;
+        vote_policy.check_reputation_quorum(vote_configuration.1 .0)?;
+        vote_policy.check_threshold(vote_configuration.1 .1)?;
+        vote_policy.check_duration(vote_configuration.1 .2.saturating_sub(current_time))?;
+        vote_policy.check_duration(vote_configuration.2 .0)?;
+        vote_policy.check_staking_limits(StakingLimits::deserialize(vote_configuration.2 .2 .1))?;
+        vote_policy.check_counting_rule(vote_configuration.2 .2 .0.into())?;
+        vote_policy.check_secret_ballot(
+            vote_configuration
+                .2
+                 .1
+                .map(SecretBallotConfiguration::deserialize),
+        )?;
         let proposal_type: ProposalType = ProposalType::Governance;
 
         let mut sponsors_mapping: BTreeMap<AccountHash, U256> = BTreeMap::new();
@@ -419,13 +519,22 @@ impl GovernanceProposal {
                 full_vote_quorum: vote_configuration.1 .0,
                 full_vote_threshold: vote_configuration.1 .1,
                 timeout: vote_configuration.1 .2,
+                full_vote_timeout: vote_configuration.2 .0,
+                voter_staking_limits: StakingLimits::deserialize(vote_configuration.2 .2 .1),
+                secret_ballot: vote_configuration
+                    .2
+                     .1
+                    .map(SecretBallotConfiguration::deserialize),
+                counting_rule: vote_configuration.2 .2 .0.into(),
             },
             proposal_type,
             proposer,
-            proposal_status: ProposalStatus::InFullVote,
+            staked_rep,
+            // Governance proposals start with an informal transition vote
+            proposal_status: ProposalStatus::WaitingFullVote,
             sponsors: sponsors_mapping,
             repository_url,
-            new_variable_key_value,
+            governance_actions: deserialized_governance_actions,
         })
     }
 
@@ -443,10 +552,11 @@ impl GovernanceProposal {
             ),
             (
                 self.proposal_status as u8,
-                (
-                    self.new_variable_key_value.0.clone(),
-                    self.new_variable_key_value.1.clone(),
-                ),
+                self.governance_actions
+                    .iter()
+                    .map(|governance_action| governance_action.serialize())
+                    .collect(),
+                self.staked_rep,
             ),
         )
     }
@@ -463,6 +573,16 @@ impl GovernanceProposal {
                 self.vote_configuration.full_vote_threshold,
                 self.vote_configuration.timeout,
             ),
+            (
+                self.vote_configuration.full_vote_timeout,
+                self.vote_configuration
+                    .secret_ballot
+                    .map(|secret_ballot| secret_ballot.serialize()),
+                (
+                    self.vote_configuration.counting_rule as u8,
+                    self.vote_configuration.voter_staking_limits.serialize(),
+                ),
+            ),
         )
     }
 
@@ -495,8 +615,8 @@ impl GovernanceProposal {
         //         // 0.2
         //         // proposal status
         //         u8,
-        //         // new_variable_key_value
-        //         (String, String),
+        //         // governance actions
+        //         Vec<GovernanceActionSerialized>,
         //     ),
         // );
         GovernanceProposal {
@@ -512,9 +632,27 @@ impl GovernanceProposal {
                 full_vote_quorum: serialized_governance_proposal.1 .2 .1 .0,
                 full_vote_threshold: serialized_governance_proposal.1 .2 .1 .1,
                 timeout: serialized_governance_proposal.1 .2 .1 .2,
+                full_vote_timeout: serialized_governance_proposal.1 .2 .2 .0,
+                voter_staking_limits: StakingLimits::deserialize(
+                    serialized_governance_proposal.1 .2 .2 .2 .1,
+                ),
+                secret_ballot: serialized_governance_proposal
+                    .1
+                     .2
+                     .2
+                     .1
+                    .map(SecretBallotConfiguration::deserialize),
+                counting_rule: serialized_governance_proposal.1 .2 .2 .2 .0.into(),
             },
             proposal_status: serialized_governance_proposal.2 .0.into(),
-            new_variable_key_value: serialized_governance_proposal.2 .1,
+            // Validated when the proposal was created
+            governance_actions: serialized_governance_proposal
+                .2
+                 .1
+                .into_iter()
+                .map(|governance_action| GovernanceAction::deserialize(governance_action).unwrap())
+                .collect(),
+            staked_rep: serialized_governance_proposal.2 .2,
         }
     }
 
@@ -529,6 +667,56 @@ impl GovernanceProposal {
     }
 }
 
+impl SecretBallotConfiguration {
+    pub fn serialize(&self) -> SecretBallotConfigurationSerialized {
+        (self.reveal_timeout, self.unrevealed_policy as u8)
+    }
+
+    pub fn deserialize(
+        serialized_secret_ballot: SecretBallotConfigurationSerialized,
+    ) -> SecretBallotConfiguration {
+        SecretBallotConfiguration {
+            reveal_timeout: serialized_secret_ballot.0,
+            unrevealed_policy: serialized_secret_ballot.1.into(),
+        }
+    }
+}
+
+impl StakingLimits {
+    pub fn serialize(&self) -> StakingLimitsSerialized {
+        (self.balance_share, self.total_stake_share)
+    }
+
+    pub fn deserialize(serialized_staking_limits: StakingLimitsSerialized) -> StakingLimits {
+        StakingLimits {
+            balance_share: serialized_staking_limits.0,
+            total_stake_share: serialized_staking_limits.1,
+        }
+    }
+}
+
+impl From<u8> for UnrevealedPolicy {
+    fn from(orig: u8) -> Self {
+        match orig {
+            0x0 => UnrevealedPolicy::Forfeit,
+            0x1 => UnrevealedPolicy::Refund,
+            _ => UnrevealedPolicy::Forfeit,
+        }
+    }
+}
+
+impl From<u8> for ProposalType {
+    fn from(orig: u8) -> Self {
+        match orig {
+            0x0 => ProposalType::Grant,
+            0x1 => ProposalType::Governance,
+            0x2 => ProposalType::AnalysisAcceptance,
+            0x3 => ProposalType::MultiOption,
+            _ => ProposalType::Grant,
+        }
+    }
+}
+
 impl From<u8> for ProposalStatus {
     fn from(orig: u8) -> Self {
         match orig {
@@ -539,3 +727,132 @@ impl From<u8> for ProposalStatus {
         };
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::vote_policy::VotePolicy;
+    use alloc::string::ToString;
+
+    const ALI: AccountHash = AccountHash::new([1u8; 32]);
+
+    fn vote_policy() -> VotePolicy {
+        VotePolicy {
+            min_member_quorum: 0,
+            min_reputation_quorum: 10,
+            min_threshold: 50,
+            min_duration: 10,
+            max_duration: 100,
+            min_balance_share: 1000,
+            max_balance_share: 10000,
+            min_total_stake_share: 0,
+            max_total_stake_share: 10000,
+            counting_rules: 1 << CountingRule::SimpleMajority as u8,
+            allow_secret_ballot: false,
+            min_reveal_timeout: 0,
+        }
+    }
+
+    fn governance_proposal(
+        governance_actions: Vec<GovernanceActionSerialized>,
+        vote_policy: Option<VotePolicy>,
+    ) -> Result<GovernanceProposal, ProposalError> {
+        GovernanceProposal::new(
+            "governance".to_string(),
+            (
+                (10, 50, "repository".to_string()),
+                (10, 50, 50),
+                (50, None, (CountingRule::SimpleMajority as u8, (1000, None))),
+            ),
+            U256::from(0),
+            ALI,
+            U256::from(100),
+            Vec::new(),
+            "url".to_string(),
+            governance_actions,
+            vote_policy,
+            0,
+        )
+    }
+
+    #[test]
+    fn test_governance_proposals_carry_a_bounded_list_of_valid_actions() {
+        let governance_proposal_with = |governance_actions: Vec<GovernanceAction>| {
+            governance_proposal(
+                governance_actions
+                    .iter()
+                    .map(GovernanceAction::serialize)
+                    .collect(),
+                Some(vote_policy()),
+            )
+        };
+        let governance_actions: Vec<GovernanceAction> = alloc::vec![
+            GovernanceAction::PolicingRatio(30),
+            GovernanceAction::SettlementPageSize(20),
+        ];
+        assert_eq!(
+            governance_proposal_with(governance_actions.clone())
+                .unwrap()
+                .governance_actions,
+            governance_actions
+        );
+        assert_eq!(
+            governance_proposal_with(Vec::new()),
+            Err(ProposalError::InvalidNumberOfGovernanceActions)
+        );
+        assert_eq!(
+            governance_proposal_with(alloc::vec![
+                GovernanceAction::PolicingRatio(30);
+                MAX_GOVERNANCE_ACTIONS + 1
+            ]),
+            Err(ProposalError::InvalidNumberOfGovernanceActions)
+        );
+        assert_eq!(
+            governance_proposal_with(alloc::vec![
+                GovernanceAction::PolicingRatio(30),
+                GovernanceAction::PolicingRatio(101),
+            ]),
+            Err(ProposalError::GovernanceValueOutOfRange)
+        );
+    }
+
+    #[test]
+    fn test_governance_proposals_are_held_to_their_vote_policy() {
+        let governance_actions: Vec<GovernanceActionSerialized> =
+            alloc::vec![GovernanceAction::PolicingRatio(30).serialize()];
+        assert_eq!(
+            governance_proposal(governance_actions.clone(), None),
+            Err(ProposalError::NoVotePolicy)
+        );
+        assert_eq!(
+            governance_proposal(
+                governance_actions.clone(),
+                Some(VotePolicy {
+                    min_reputation_quorum: 11,
+                    ..vote_policy()
+                })
+            ),
+            Err(ProposalError::QuorumBelowPolicy)
+        );
+        assert_eq!(
+            governance_proposal(
+                governance_actions.clone(),
+                Some(VotePolicy {
+                    min_balance_share: 1001,
+                    ..vote_policy()
+                })
+            ),
+            Err(ProposalError::StakingLimitsBelowPolicy)
+        );
+        assert_eq!(
+            governance_proposal(
+                governance_actions,
+                Some(VotePolicy {
+                    counting_rules: 1 << CountingRule::Supermajority as u8,
+                    ..vote_policy()
+                })
+            ),
+            Err(ProposalError::CountingRuleNotAllowed)
+        );
+    }
+}
@@ -0,0 +1,151 @@
+extern crate alloc;
+use crate::custom_types::custom_types::ReputationLocksSerialized;
+use alloc::collections::BTreeMap;
+use core::cmp::{Eq, Ord, PartialEq, PartialOrd};
+use types::{account::AccountHash, U256};
+
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone, Copy)]
+pub enum VoteType {
+    Binary,
+    MultiOption,
+}
+
+// Reputation a member has at stake, per vote, until it is claimed, refunded or the vote ends
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone)]
+pub struct ReputationLocks {
+    pub account: AccountHash,
+    pub locks: BTreeMap<(VoteType, U256), U256>,
+}
+
+impl ReputationLocks {
+    pub fn new(account: AccountHash) -> ReputationLocks {
+        ReputationLocks {
+            account,
+            locks: BTreeMap::new(),
+        }
+    }
+
+    pub fn locked_reputation(&self) -> U256 {
+        self.locks
+            .values()
+            .fold(U256::from(0), |total, locked| total + *locked)
+    }
+
+    // Reputation still free to stake out of `reputation_balance`
+    pub fn free_reputation(&self, reputation_balance: U256) -> U256 {
+        reputation_balance.saturating_sub(self.locked_reputation())
+    }
+
+    pub fn locked_on(&self, vote_type: VoteType, vote_index: U256) -> U256 {
+        match self.locks.get(&(vote_type, vote_index)) {
+            Some(locked) => *locked,
+            None => U256::from(0),
+        }
+    }
+
+    pub fn lock(&mut self, vote_type: VoteType, vote_index: U256, amount: U256) {
+        if amount == U256::from(0) {
+            return;
+        }
+        let locked: U256 = self.locked_on(vote_type, vote_index);
+        self.locks.insert((vote_type, vote_index), locked + amount);
+    }
+
+    // Returns the released amount, never more than what is locked on the vote
+    pub fn release(&mut self, vote_type: VoteType, vote_index: U256, amount: U256) -> U256 {
+        let locked: U256 = self.locked_on(vote_type, vote_index);
+        let released: U256 = core::cmp::min(locked, amount);
+        if released == locked {
+            self.locks.remove(&(vote_type, vote_index));
+        } else {
+            self.locks
+                .insert((vote_type, vote_index), locked - released);
+        }
+        released
+    }
+
+    pub fn release_all(&mut self, vote_type: VoteType, vote_index: U256) -> U256 {
+        self.locks
+            .remove(&(vote_type, vote_index))
+            .unwrap_or(U256::from(0))
+    }
+
+    pub fn serialize(&self) -> ReputationLocksSerialized {
+        let mut locks_output: BTreeMap<(u8, U256), U256> = BTreeMap::new();
+        for ((vote_type, vote_index), locked) in self.locks.iter() {
+            locks_output.insert((*vote_type as u8, *vote_index), *locked);
+        }
+        (self.account.value(), locks_output)
+    }
+
+    pub fn deserialize(serialized_locks: ReputationLocksSerialized) -> ReputationLocks {
+        let mut locks: BTreeMap<(VoteType, U256), U256> = BTreeMap::new();
+        for ((vote_type, vote_index), locked) in serialized_locks.1 {
+            locks.insert((vote_type.into(), vote_index), locked);
+        }
+        ReputationLocks {
+            account: AccountHash::new(serialized_locks.0),
+            locks,
+        }
+    }
+}
+
+impl From<u8> for VoteType {
+    fn from(orig: u8) -> Self {
+        match orig {
+            0 => VoteType::Binary,
+            1 => VoteType::MultiOption,
+            _ => VoteType::Binary,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ALI: AccountHash = AccountHash::new([1u8; 32]);
+
+    #[test]
+    fn test_locks_are_kept_per_vote() {
+        let mut locks = ReputationLocks::new(ALI);
+        locks.lock(VoteType::Binary, U256::from(0), U256::from(30));
+        locks.lock(VoteType::Binary, U256::from(0), U256::from(10));
+        locks.lock(VoteType::MultiOption, U256::from(0), U256::from(20));
+        assert_eq!(locks.locked_reputation(), U256::from(60));
+        assert_eq!(locks.free_reputation(U256::from(100)), U256::from(40));
+        // Balances that fell below the locks leave nothing free
+        assert_eq!(locks.free_reputation(U256::from(50)), U256::from(0));
+
+        assert_eq!(
+            locks.release(VoteType::Binary, U256::from(0), U256::from(15)),
+            U256::from(15)
+        );
+        assert_eq!(
+            locks.locked_on(VoteType::Binary, U256::from(0)),
+            U256::from(25)
+        );
+        // Never more than what is locked on the vote
+        assert_eq!(
+            locks.release(VoteType::Binary, U256::from(0), U256::from(100)),
+            U256::from(25)
+        );
+        assert_eq!(
+            locks.release_all(VoteType::MultiOption, U256::from(0)),
+            U256::from(20)
+        );
+        assert_eq!(
+            locks.release_all(VoteType::MultiOption, U256::from(0)),
+            U256::from(0)
+        );
+        assert!(locks.locks.is_empty());
+    }
+
+    #[test]
+    fn test_serialization() {
+        let mut locks = ReputationLocks::new(ALI);
+        locks.lock(VoteType::Binary, U256::from(3), U256::from(30));
+        locks.lock(VoteType::MultiOption, U256::from(1), U256::from(20));
+        assert_eq!(ReputationLocks::deserialize(locks.serialize()), locks);
+    }
+}
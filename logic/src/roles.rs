@@ -0,0 +1,152 @@
+extern crate alloc;
+use crate::custom_types::custom_types::RoleRegistrySerialized;
+use alloc::collections::{BTreeMap, BTreeSet};
+use alloc::vec::Vec;
+use core::cmp::{Eq, Ord, PartialEq, PartialOrd};
+use types::account::AccountHash;
+
+pub const NUMBER_OF_ROLES: u8 = 6;
+
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone, Copy)]
+pub enum Role {
+    // Pauses operations and vetoes queued governance actions
+    Failsafe,
+    // Manages members, vetoes queued governance actions
+    Compliance,
+    VotingEngine,
+    ExecutionEngine,
+    // Submits milestone analyses
+    Reviewer,
+    // Opens votes for new proposals
+    ProposalEngine,
+}
+
+impl Role {
+    // Roles held by DAO contracts, proven with the contract's engine key rather than the
+    // caller, which is always the account of the deploy
+    pub fn is_engine(&self) -> bool {
+        matches!(
+            self,
+            Role::VotingEngine | Role::ExecutionEngine | Role::ProposalEngine
+        )
+    }
+}
+
+// Accounts holding each role, the address of its engine key for engine roles, shared by
+// every DAO contract through the governance contract
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone, Default)]
+pub struct RoleRegistry {
+    pub members: BTreeMap<Role, BTreeSet<AccountHash>>,
+}
+
+impl RoleRegistry {
+    pub fn new() -> RoleRegistry {
+        RoleRegistry::default()
+    }
+
+    pub fn has_role(&self, role: Role, account: &AccountHash) -> bool {
+        match self.members.get(&role) {
+            Some(members) => members.contains(account),
+            None => false,
+        }
+    }
+
+    pub fn role_members(&self, role: Role) -> Vec<AccountHash> {
+        match self.members.get(&role) {
+            Some(members) => members.iter().copied().collect(),
+            None => Vec::new(),
+        }
+    }
+
+    // Returns whether the account didn't hold the role yet
+    pub fn grant(&mut self, role: Role, account: AccountHash) -> bool {
+        self.members.entry(role).or_default().insert(account)
+    }
+
+    // Returns whether the account held the role
+    pub fn revoke(&mut self, role: Role, account: &AccountHash) -> bool {
+        match self.members.get_mut(&role) {
+            Some(members) => members.remove(account),
+            None => false,
+        }
+    }
+
+    pub fn serialize(&self) -> RoleRegistrySerialized {
+        let mut output: RoleRegistrySerialized = BTreeMap::new();
+        for (role, members) in self.members.iter() {
+            output.insert(
+                *role as u8,
+                members.iter().map(|member| member.value()).collect(),
+            );
+        }
+        output
+    }
+
+    pub fn deserialize(serialized_registry: RoleRegistrySerialized) -> RoleRegistry {
+        let mut members: BTreeMap<Role, BTreeSet<AccountHash>> = BTreeMap::new();
+        for (role, role_members) in serialized_registry {
+            members.insert(
+                role.into(),
+                role_members.into_iter().map(AccountHash::new).collect(),
+            );
+        }
+        RoleRegistry { members }
+    }
+}
+
+impl From<u8> for Role {
+    fn from(orig: u8) -> Self {
+        match orig {
+            0x0 => Role::Failsafe,
+            0x1 => Role::Compliance,
+            0x2 => Role::VotingEngine,
+            0x3 => Role::ExecutionEngine,
+            0x4 => Role::Reviewer,
+            0x5 => Role::ProposalEngine,
+            _ => Role::Reviewer,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ALI: AccountHash = AccountHash::new([1u8; 32]);
+    const BOB: AccountHash = AccountHash::new([2u8; 32]);
+
+    #[test]
+    fn test_grant_and_revoke_roles() {
+        let mut role_registry: RoleRegistry = RoleRegistry::new();
+        assert!(!role_registry.has_role(Role::Failsafe, &ALI));
+        assert!(role_registry.grant(Role::Failsafe, ALI));
+        assert!(!role_registry.grant(Role::Failsafe, ALI));
+        assert!(role_registry.grant(Role::Failsafe, BOB));
+        assert!(role_registry.has_role(Role::Failsafe, &ALI));
+        assert!(!role_registry.has_role(Role::Compliance, &ALI));
+        assert_eq!(
+            role_registry.role_members(Role::Failsafe),
+            alloc::vec![ALI, BOB]
+        );
+        assert!(role_registry.revoke(Role::Failsafe, &ALI));
+        assert!(!role_registry.revoke(Role::Failsafe, &ALI));
+        assert!(!role_registry.revoke(Role::Reviewer, &ALI));
+        assert_eq!(role_registry.role_members(Role::Failsafe), alloc::vec![BOB]);
+        assert_eq!(
+            RoleRegistry::deserialize(role_registry.serialize()),
+            role_registry
+        );
+    }
+
+    #[test]
+    fn test_engine_roles() {
+        assert!(Role::VotingEngine.is_engine());
+        assert!(Role::ExecutionEngine.is_engine());
+        assert!(Role::ProposalEngine.is_engine());
+        assert!(!Role::Failsafe.is_engine());
+        assert!(!Role::Reviewer.is_engine());
+        for role in 0..NUMBER_OF_ROLES {
+            assert_eq!(Role::from(role) as u8, role);
+        }
+    }
+}
@@ -0,0 +1,133 @@
+extern crate alloc;
+use crate::{
+    error::*,
+    proposal::ProposalType,
+    voting::{VoteDirection, VoteResult, Voting},
+};
+use alloc::collections::BTreeMap;
+use alloc::vec::Vec;
+use types::{account::AccountHash, U256};
+
+// Projects the result of `voting` if the hypothetical ballots (voter, reputation balance,
+// stake, direction) were cast and the vote closed at `finalization_time`, with the reputation
+// every voter would gain, stakes are only locked. The vote passed in is left untouched.
+pub fn simulate_vote(
+    voting: &Voting,
+    ballots: Vec<(AccountHash, U256, U256, VoteDirection)>,
+    finalization_time: u64,
+    reputation_allocation_ratio: u64,
+) -> Result<(VoteResult, BTreeMap<AccountHash, U256>), VotingEngineError> {
+    let mut simulated_voting: Voting = voting.clone();
+    // Ballots are cast as the vote opens, so no late swing extension applies
+    let cast_time: u64 = core::cmp::max(
+        simulated_voting.start_at(),
+        simulated_voting.full_vote_start_timestamp,
+    );
+    for (voter, reputation_balance, reputation_to_stake, vote_direction) in ballots {
+        simulated_voting.cast_vote(
+            voter,
+            cast_time,
+            reputation_balance,
+            reputation_to_stake,
+            U256::from(0),
+            vote_direction,
+        )?;
+    }
+    let result: VoteResult = if simulated_voting.proposal_type == ProposalType::Governance {
+        simulated_voting.calculate_governance_vote_outcome(finalization_time)?
+    } else {
+        simulated_voting.calculate_vote_outcome(finalization_time, reputation_allocation_ratio)?
+    };
+    let voters: Vec<AccountHash> = simulated_voting
+        .for_voters
+        .keys()
+        .chain(simulated_voting.against_voters.keys())
+        .chain(simulated_voting.abstain_voters.keys())
+        .copied()
+        .collect();
+    let mut claimable_reputation: BTreeMap<AccountHash, U256> = BTreeMap::new();
+    for voter in voters {
+        // Claims that would revert, as on a failed vote, gain nothing
+        let claimable: U256 = simulated_voting
+            .claim_reputation(voter)
+            .unwrap_or(U256::from(0));
+        claimable_reputation.insert(voter, claimable);
+    }
+    Ok((result, claimable_reputation))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{vote_counting::CountingRule, voting::tests::grant_vote};
+
+    const ALI: AccountHash = AccountHash::new([1u8; 32]);
+    const BOB: AccountHash = AccountHash::new([2u8; 32]);
+    const JON: AccountHash = AccountHash::new([3u8; 32]);
+
+    #[test]
+    fn test_simulation_projects_claims_without_touching_the_vote() {
+        let voting: Voting = grant_vote(CountingRule::SimpleMajority, None);
+        let (result, claimable_reputation) = simulate_vote(
+            &voting,
+            alloc::vec![
+                (ALI, U256::from(100), U256::from(30), VoteDirection::For),
+                (BOB, U256::from(100), U256::from(10), VoteDirection::For),
+                (JON, U256::from(100), U256::from(20), VoteDirection::Against),
+            ],
+            101,
+            0,
+        )
+        .unwrap();
+        assert_eq!(result, VoteResult::Approved);
+        assert_eq!(claimable_reputation.get(&ALI), Some(&U256::from(15)));
+        assert_eq!(claimable_reputation.get(&BOB), Some(&U256::from(5)));
+        assert_eq!(claimable_reputation.get(&JON), Some(&U256::from(0)));
+        assert_eq!(voting, grant_vote(CountingRule::SimpleMajority, None));
+    }
+
+    #[test]
+    fn test_simulation_surfaces_invalid_ballots_and_open_votes() {
+        let voting: Voting = grant_vote(CountingRule::SimpleMajority, None);
+        assert_eq!(
+            simulate_vote(
+                &voting,
+                alloc::vec![(ALI, U256::from(10), U256::from(30), VoteDirection::For)],
+                101,
+                0,
+            ),
+            Err(VotingEngineError::InvalidReputationToStake)
+        );
+        // JON alone could still swing the vote
+        assert_eq!(
+            simulate_vote(
+                &voting,
+                alloc::vec![
+                    (ALI, U256::from(100), U256::from(30), VoteDirection::For),
+                    (BOB, U256::from(100), U256::from(10), VoteDirection::For),
+                ],
+                50,
+                0,
+            ),
+            Err(VotingEngineError::VotingOngoing)
+        );
+    }
+
+    #[test]
+    fn test_simulation_finalizes_early_once_the_outcome_is_locked() {
+        let voting: Voting = grant_vote(CountingRule::SimpleMajority, None);
+        let (result, claimable_reputation) = simulate_vote(
+            &voting,
+            alloc::vec![
+                (ALI, U256::from(100), U256::from(30), VoteDirection::For),
+                (BOB, U256::from(100), U256::from(10), VoteDirection::For),
+                (JON, U256::from(100), U256::from(20), VoteDirection::Against),
+            ],
+            50,
+            0,
+        )
+        .unwrap();
+        assert_eq!(result, VoteResult::Approved);
+        assert_eq!(claimable_reputation.get(&ALI), Some(&U256::from(15)));
+    }
+}
@@ -0,0 +1,295 @@
+extern crate alloc;
+use crate::voting::{VoteDirection, VoteResult, VotingData};
+use core::cmp::{Eq, Ord, PartialEq, PartialOrd};
+use types::U256;
+
+// Share of the counted votes the for side must reach under the supermajority rule
+pub const SUPERMAJORITY_NUMERATOR: u64 = 2;
+pub const SUPERMAJORITY_DENOMINATOR: u64 = 3;
+
+// Decides how ballots are weighted and which side wins a vote
+pub trait VoteCountingRule {
+    // Voting power of a ballot backed by `reputation_staked`
+    fn ballot_weight(&self, reputation_staked: U256) -> U256;
+
+    // Most weight `remaining_members` voters holding `remaining_reputation` between them can add
+    fn max_weight(&self, remaining_reputation: U256, _remaining_members: u64) -> U256 {
+        self.ballot_weight(remaining_reputation)
+    }
+
+    // The for side must exceed the threshold percentage of the counted votes for the vote to
+    // pass, compared cross-multiplied so no fraction of a percent is lost
+    fn passes(&self, for_weight: U256, total_weight: U256, threshold: u64) -> bool {
+        for_weight * U256::from(100) > total_weight * U256::from(threshold)
+    }
+
+    // Same for the against side to reject the vote
+    fn fails(&self, against_weight: U256, total_weight: U256, threshold: u64) -> bool {
+        against_weight * U256::from(100) > total_weight * U256::from(threshold)
+    }
+
+    // Returns the (for, against) weights of the ballots, abstentions don't count
+    fn tally(&self, ballots: &mut dyn Iterator<Item = &VotingData>) -> (U256, U256) {
+        let mut for_weight: U256 = U256::from(0);
+        let mut against_weight: U256 = U256::from(0);
+        for ballot in ballots {
+            match ballot.vote {
+                VoteDirection::For => for_weight += self.ballot_weight(ballot.reputation_staked),
+                VoteDirection::Against => {
+                    against_weight += self.ballot_weight(ballot.reputation_staked)
+                }
+                VoteDirection::Abstain => (),
+            }
+        }
+        (for_weight, against_weight)
+    }
+
+    // Ties go to against
+    fn count(&self, for_weight: U256, against_weight: U256, threshold: u64) -> VoteResult {
+        let total_weight: U256 = for_weight + against_weight;
+        if total_weight == U256::from(0) {
+            return VoteResult::FailThresholdUnmet;
+        }
+        if for_weight > against_weight {
+            if self.passes(for_weight, total_weight, threshold) {
+                VoteResult::Approved
+            } else {
+                VoteResult::PassThresholdUnmet
+            }
+        } else if self.fails(against_weight, total_weight, threshold) {
+            VoteResult::Rejected
+        } else {
+            VoteResult::FailThresholdUnmet
+        }
+    }
+}
+
+// One unit of staked reputation is one vote
+pub struct SimpleMajority;
+
+// Like the simple majority, but passing needs at least two thirds of the votes
+pub struct Supermajority;
+
+// Ballots weigh the square root of the staked reputation
+pub struct QuadraticWeighting;
+
+// Every voter has a single vote whatever they stake
+pub struct OneMemberOneVote;
+
+impl VoteCountingRule for SimpleMajority {
+    fn ballot_weight(&self, reputation_staked: U256) -> U256 {
+        reputation_staked
+    }
+}
+
+impl VoteCountingRule for Supermajority {
+    fn ballot_weight(&self, reputation_staked: U256) -> U256 {
+        reputation_staked
+    }
+
+    // Exactly two thirds is enough, a higher threshold still applies
+    fn passes(&self, for_weight: U256, total_weight: U256, threshold: u64) -> bool {
+        for_weight * U256::from(SUPERMAJORITY_DENOMINATOR)
+            >= total_weight * U256::from(SUPERMAJORITY_NUMERATOR)
+            && SimpleMajority.passes(for_weight, total_weight, threshold)
+    }
+}
+
+impl VoteCountingRule for QuadraticWeighting {
+    fn ballot_weight(&self, reputation_staked: U256) -> U256 {
+        integer_sqrt(reputation_staked)
+    }
+
+    // Splitting the reputation evenly between the voters gives the most weight
+    fn max_weight(&self, remaining_reputation: U256, remaining_members: u64) -> U256 {
+        integer_sqrt(remaining_reputation * U256::from(remaining_members))
+    }
+}
+
+impl VoteCountingRule for OneMemberOneVote {
+    fn ballot_weight(&self, _reputation_staked: U256) -> U256 {
+        U256::from(1)
+    }
+
+    fn max_weight(&self, _remaining_reputation: U256, remaining_members: u64) -> U256 {
+        U256::from(remaining_members)
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone, Copy)]
+pub enum CountingRule {
+    SimpleMajority,
+    Supermajority,
+    QuadraticWeighting,
+    OneMemberOneVote,
+}
+
+impl CountingRule {
+    pub fn rule(&self) -> &'static dyn VoteCountingRule {
+        match self {
+            CountingRule::SimpleMajority => &SimpleMajority,
+            CountingRule::Supermajority => &Supermajority,
+            CountingRule::QuadraticWeighting => &QuadraticWeighting,
+            CountingRule::OneMemberOneVote => &OneMemberOneVote,
+        }
+    }
+}
+
+// Largest integer whose square doesn't exceed `value` (Newton's method)
+fn integer_sqrt(value: U256) -> U256 {
+    if value < U256::from(2) {
+        return value;
+    }
+    let mut current: U256 = value;
+    let mut next: U256 = value / 2 + value % 2;
+    while next < current {
+        current = next;
+        next = (current + value / current) / 2;
+    }
+    current
+}
+
+impl From<u8> for CountingRule {
+    fn from(orig: u8) -> Self {
+        match orig {
+            0 => CountingRule::SimpleMajority,
+            1 => CountingRule::Supermajority,
+            2 => CountingRule::QuadraticWeighting,
+            3 => CountingRule::OneMemberOneVote,
+            _ => CountingRule::SimpleMajority,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::vec::Vec;
+
+    fn ballot(vote: VoteDirection, reputation_staked: u64) -> VotingData {
+        VotingData {
+            reputation_staked: U256::from(reputation_staked),
+            vote,
+            claimed: false,
+            delegate: None,
+        }
+    }
+
+    fn count(
+        rule: CountingRule,
+        for_weight: u64,
+        against_weight: u64,
+        threshold: u64,
+    ) -> VoteResult {
+        rule.rule().count(
+            U256::from(for_weight),
+            U256::from(against_weight),
+            threshold,
+        )
+    }
+
+    #[test]
+    fn test_simple_majority() {
+        assert_eq!(
+            count(CountingRule::SimpleMajority, 51, 49, 50),
+            VoteResult::Approved
+        );
+        assert_eq!(
+            count(CountingRule::SimpleMajority, 49, 51, 50),
+            VoteResult::Rejected
+        );
+        // Ties go to against, without reaching the fail threshold
+        assert_eq!(
+            count(CountingRule::SimpleMajority, 50, 50, 50),
+            VoteResult::FailThresholdUnmet
+        );
+        assert_eq!(
+            count(CountingRule::SimpleMajority, 0, 0, 50),
+            VoteResult::FailThresholdUnmet
+        );
+        assert_eq!(
+            count(CountingRule::SimpleMajority, 60, 40, 60),
+            VoteResult::PassThresholdUnmet
+        );
+    }
+
+    #[test]
+    fn test_threshold_keeps_fractions_of_a_percent() {
+        // 50.5% exceeds a 50% threshold
+        assert_eq!(
+            count(CountingRule::SimpleMajority, 505, 495, 50),
+            VoteResult::Approved
+        );
+        // 60.01% exceeds a 60% threshold
+        assert_eq!(
+            count(CountingRule::SimpleMajority, 6001, 3999, 60),
+            VoteResult::Approved
+        );
+    }
+
+    #[test]
+    fn test_supermajority_needs_two_thirds() {
+        assert_eq!(
+            count(CountingRule::Supermajority, 2, 1, 50),
+            VoteResult::Approved
+        );
+        assert_eq!(
+            count(CountingRule::Supermajority, 200, 100, 0),
+            VoteResult::Approved
+        );
+        assert_eq!(
+            count(CountingRule::Supermajority, 66, 34, 50),
+            VoteResult::PassThresholdUnmet
+        );
+        // A higher threshold still applies
+        assert_eq!(
+            count(CountingRule::Supermajority, 75, 25, 80),
+            VoteResult::PassThresholdUnmet
+        );
+        assert_eq!(
+            count(CountingRule::Supermajority, 81, 19, 80),
+            VoteResult::Approved
+        );
+    }
+
+    #[test]
+    fn test_ballot_weights() {
+        let ballots: Vec<VotingData> = alloc::vec![
+            ballot(VoteDirection::For, 100),
+            ballot(VoteDirection::For, 99),
+            ballot(VoteDirection::Against, 400),
+            ballot(VoteDirection::Abstain, 1000),
+        ];
+        assert_eq!(
+            CountingRule::SimpleMajority
+                .rule()
+                .tally(&mut ballots.iter()),
+            (U256::from(199), U256::from(400))
+        );
+        // Square roots are rounded down
+        assert_eq!(
+            CountingRule::QuadraticWeighting
+                .rule()
+                .tally(&mut ballots.iter()),
+            (U256::from(19), U256::from(20))
+        );
+        assert_eq!(
+            CountingRule::OneMemberOneVote
+                .rule()
+                .tally(&mut ballots.iter()),
+            (U256::from(2), U256::from(1))
+        );
+    }
+
+    #[test]
+    fn test_integer_sqrt() {
+        assert_eq!(integer_sqrt(U256::from(0)), U256::from(0));
+        assert_eq!(integer_sqrt(U256::from(1)), U256::from(1));
+        assert_eq!(integer_sqrt(U256::from(15)), U256::from(3));
+        assert_eq!(integer_sqrt(U256::from(16)), U256::from(4));
+        assert_eq!(
+            integer_sqrt(U256::MAX),
+            U256::from(2).pow(U256::from(128)) - 1
+        );
+    }
+}
@@ -0,0 +1,145 @@
+extern crate alloc;
+use crate::{
+    custom_types::custom_types::VoteOutcomeReportSerialized,
+    governance_action::GovernanceActionResult, voting::VoteResult,
+};
+use alloc::vec::Vec;
+use core::cmp::{min, Eq, Ord, PartialEq, PartialOrd};
+use types::U256;
+
+// How a closed vote reached its result, percentages are rounded down
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone)]
+pub struct VoteOutcomeReport {
+    pub result: VoteResult,
+    pub member_quorum_met: bool,
+    pub reputation_quorum_met: bool,
+    // The tally reached the pass or the fail threshold
+    pub threshold_met: bool,
+    pub total_members: u64,
+    pub eligible_members: u64,
+    pub for_votes: U256,
+    pub against_votes: U256,
+    pub abstain_votes: U256,
+    // Share of the eligible members who voted
+    pub member_participation: u64,
+    // Share of the eligible reputation staked
+    pub reputation_participation: u64,
+    // Share of the counted weight in favor, abstentions excluded
+    pub for_percentage: u64,
+    pub input_reputation: U256,
+    // One per action of an approved governance proposal
+    pub action_results: Vec<GovernanceActionResult>,
+}
+
+impl VoteOutcomeReport {
+    pub fn percentage(part: U256, total: U256) -> u64 {
+        if total == U256::from(0) {
+            return 0;
+        }
+        let part: U256 = min(part, total);
+        if total > U256::MAX / U256::from(100) {
+            // Scale the total down first so the product can't overflow
+            return min((part / (total / U256::from(100))).as_u64(), 100);
+        }
+        ((part * U256::from(100)) / total).as_u64()
+    }
+
+    pub fn serialize(&self) -> VoteOutcomeReportSerialized {
+        (
+            (
+                self.result as u8,
+                (
+                    self.member_quorum_met,
+                    self.reputation_quorum_met,
+                    self.threshold_met,
+                ),
+                (self.total_members, self.eligible_members),
+            ),
+            (self.for_votes, self.against_votes, self.abstain_votes),
+            (
+                (
+                    self.member_participation,
+                    self.reputation_participation,
+                    self.for_percentage,
+                ),
+                self.input_reputation,
+                self.action_results
+                    .iter()
+                    .map(|action_result| *action_result as u8)
+                    .collect(),
+            ),
+        )
+    }
+
+    pub fn deserialize(serialized_report: VoteOutcomeReportSerialized) -> VoteOutcomeReport {
+        VoteOutcomeReport {
+            result: serialized_report.0 .0.into(),
+            member_quorum_met: serialized_report.0 .1 .0,
+            reputation_quorum_met: serialized_report.0 .1 .1,
+            threshold_met: serialized_report.0 .1 .2,
+            total_members: serialized_report.0 .2 .0,
+            eligible_members: serialized_report.0 .2 .1,
+            for_votes: serialized_report.1 .0,
+            against_votes: serialized_report.1 .1,
+            abstain_votes: serialized_report.1 .2,
+            member_participation: serialized_report.2 .0 .0,
+            reputation_participation: serialized_report.2 .0 .1,
+            for_percentage: serialized_report.2 .0 .2,
+            input_reputation: serialized_report.2 .1,
+            action_results: serialized_report
+                .2
+                 .2
+                .into_iter()
+                .map(GovernanceActionResult::from)
+                .collect(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_percentage() {
+        assert_eq!(
+            VoteOutcomeReport::percentage(U256::from(2), U256::from(3)),
+            66
+        );
+        assert_eq!(
+            VoteOutcomeReport::percentage(U256::from(5), U256::from(0)),
+            0
+        );
+        // Balances near the top of the range don't overflow
+        assert_eq!(VoteOutcomeReport::percentage(U256::MAX / 2, U256::MAX), 50);
+        assert_eq!(VoteOutcomeReport::percentage(U256::MAX, U256::MAX), 100);
+        assert_eq!(
+            VoteOutcomeReport::percentage(U256::from(4), U256::from(3)),
+            100
+        );
+    }
+
+    #[test]
+    fn test_serialization() {
+        let report: VoteOutcomeReport = VoteOutcomeReport {
+            result: VoteResult::Approved,
+            member_quorum_met: true,
+            reputation_quorum_met: false,
+            threshold_met: true,
+            total_members: 2,
+            eligible_members: 3,
+            for_votes: U256::from(30),
+            against_votes: U256::from(10),
+            abstain_votes: U256::from(5),
+            member_participation: 66,
+            reputation_participation: 15,
+            for_percentage: 75,
+            input_reputation: U256::from(7),
+            action_results: alloc::vec![
+                GovernanceActionResult::Applied,
+                GovernanceActionResult::NotApplied
+            ],
+        };
+        assert_eq!(VoteOutcomeReport::deserialize(report.serialize()), report);
+    }
+}
@@ -0,0 +1,333 @@
+extern crate alloc;
+use crate::{
+    custom_types::custom_types::{VotePoliciesSerialized, VotePolicySerialized},
+    error::*,
+    fixed_point::BASIS_POINTS,
+    proposal::{ProposalType, SecretBallotConfiguration, StakingLimits},
+    vote_counting::CountingRule,
+};
+use alloc::collections::BTreeMap;
+use core::cmp::{Eq, Ord, PartialEq, PartialOrd};
+use types::U256;
+
+// Bounds set by the DAO on the vote configuration a proposer picks, quorums are percentages,
+// staking limits are in basis points and durations are in the same unit as the block time
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone, Copy)]
+pub struct VotePolicy {
+    pub min_member_quorum: u64,
+    pub min_reputation_quorum: u64,
+    pub min_threshold: u64,
+    pub min_duration: u64,
+    pub max_duration: u64,
+    // Range of the caps a proposer may put on voter stakes, a total stake cap is required unless
+    // its highest value is BASIS_POINTS
+    pub min_balance_share: u64,
+    pub max_balance_share: u64,
+    pub min_total_stake_share: u64,
+    pub max_total_stake_share: u64,
+    // One bit per allowed counting rule, by its serialized value
+    pub counting_rules: u8,
+    pub allow_secret_ballot: bool,
+    pub min_reveal_timeout: u64,
+}
+
+// A cost band starts at its lowest cost and runs up to the next band of the same proposal type
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone, Default)]
+pub struct VotePolicies {
+    pub policies: BTreeMap<(ProposalType, U256), VotePolicy>,
+}
+
+impl VotePolicy {
+    pub fn check_member_quorum(&self, member_quorum: u64) -> Result<(), ProposalError> {
+        if member_quorum < self.min_member_quorum {
+            return Err(ProposalError::QuorumBelowPolicy);
+        }
+        Ok(())
+    }
+
+    pub fn check_reputation_quorum(&self, reputation_quorum: u64) -> Result<(), ProposalError> {
+        if reputation_quorum < self.min_reputation_quorum {
+            return Err(ProposalError::QuorumBelowPolicy);
+        }
+        Ok(())
+    }
+
+    pub fn check_threshold(&self, threshold: u64) -> Result<(), ProposalError> {
+        if threshold < self.min_threshold {
+            return Err(ProposalError::ThresholdBelowPolicy);
+        }
+        Ok(())
+    }
+
+    pub fn check_duration(&self, duration: u64) -> Result<(), ProposalError> {
+        if duration < self.min_duration || duration > self.max_duration {
+            return Err(ProposalError::DurationOutsidePolicy);
+        }
+        Ok(())
+    }
+
+    // Caps below the policy would keep members from staking, caps above it would let a few of
+    // them dominate the vote
+    pub fn check_staking_limits(&self, staking_limits: StakingLimits) -> Result<(), ProposalError> {
+        let total_stake_share: u64 = staking_limits.total_stake_share.unwrap_or(BASIS_POINTS);
+        if staking_limits.balance_share < self.min_balance_share
+            || total_stake_share < self.min_total_stake_share
+        {
+            return Err(ProposalError::StakingLimitsBelowPolicy);
+        }
+        if staking_limits.balance_share > self.max_balance_share
+            || total_stake_share > self.max_total_stake_share
+        {
+            return Err(ProposalError::StakingLimitsAbovePolicy);
+        }
+        Ok(())
+    }
+
+    pub fn check_counting_rule(&self, counting_rule: CountingRule) -> Result<(), ProposalError> {
+        if self.counting_rules & (1 << counting_rule as u8) == 0 {
+            return Err(ProposalError::CountingRuleNotAllowed);
+        }
+        Ok(())
+    }
+
+    pub fn check_secret_ballot(
+        &self,
+        secret_ballot: Option<SecretBallotConfiguration>,
+    ) -> Result<(), ProposalError> {
+        match secret_ballot {
+            Some(secret_ballot)
+                if !self.allow_secret_ballot
+                    || secret_ballot.reveal_timeout < self.min_reveal_timeout =>
+            {
+                Err(ProposalError::SecretBallotOutsidePolicy)
+            }
+            _ => Ok(()),
+        }
+    }
+
+    pub fn serialize(&self) -> VotePolicySerialized {
+        (
+            (
+                self.min_member_quorum,
+                self.min_reputation_quorum,
+                self.min_threshold,
+            ),
+            (self.min_duration, self.max_duration),
+            (
+                (
+                    (self.min_balance_share, self.max_balance_share),
+                    (self.min_total_stake_share, self.max_total_stake_share),
+                ),
+                self.counting_rules,
+                (self.allow_secret_ballot, self.min_reveal_timeout),
+            ),
+        )
+    }
+
+    pub fn deserialize(serialized_policy: VotePolicySerialized) -> VotePolicy {
+        VotePolicy {
+            min_member_quorum: serialized_policy.0 .0,
+            min_reputation_quorum: serialized_policy.0 .1,
+            min_threshold: serialized_policy.0 .2,
+            min_duration: serialized_policy.1 .0,
+            max_duration: serialized_policy.1 .1,
+            min_balance_share: serialized_policy.2 .0 .0 .0,
+            max_balance_share: serialized_policy.2 .0 .0 .1,
+            min_total_stake_share: serialized_policy.2 .0 .1 .0,
+            max_total_stake_share: serialized_policy.2 .0 .1 .1,
+            counting_rules: serialized_policy.2 .1,
+            allow_secret_ballot: serialized_policy.2 .2 .0,
+            min_reveal_timeout: serialized_policy.2 .2 .1,
+        }
+    }
+}
+
+impl VotePolicies {
+    pub fn new() -> VotePolicies {
+        VotePolicies::default()
+    }
+
+    pub fn set(&mut self, proposal_type: ProposalType, min_cost: U256, policy: VotePolicy) {
+        self.policies.insert((proposal_type, min_cost), policy);
+    }
+
+    // Policy of the band `cost` falls in, None when the DAO set no policy for it, the
+    // governance contract seeds one for every proposal type from cost 0
+    pub fn policy_for(&self, proposal_type: ProposalType, cost: U256) -> Option<VotePolicy> {
+        self.policies
+            .range((proposal_type, U256::from(0))..=(proposal_type, cost))
+            .next_back()
+            .map(|(_, policy)| *policy)
+    }
+
+    pub fn serialize(&self) -> VotePoliciesSerialized {
+        let mut policies_output: VotePoliciesSerialized = BTreeMap::new();
+        for ((proposal_type, min_cost), policy) in self.policies.iter() {
+            policies_output.insert((*proposal_type as u8, *min_cost), policy.serialize());
+        }
+        policies_output
+    }
+
+    pub fn deserialize(serialized_policies: VotePoliciesSerialized) -> VotePolicies {
+        let mut policies: BTreeMap<(ProposalType, U256), VotePolicy> = BTreeMap::new();
+        for ((proposal_type, min_cost), policy) in serialized_policies {
+            policies.insert(
+                (proposal_type.into(), min_cost),
+                VotePolicy::deserialize(policy),
+            );
+        }
+        VotePolicies { policies }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::proposal::UnrevealedPolicy;
+
+    fn policy(min_member_quorum: u64) -> VotePolicy {
+        VotePolicy {
+            min_member_quorum,
+            min_reputation_quorum: 10,
+            min_threshold: 50,
+            min_duration: 10,
+            max_duration: 100,
+            min_balance_share: 1000,
+            max_balance_share: 10000,
+            min_total_stake_share: 500,
+            max_total_stake_share: 10000,
+            counting_rules: 1 << CountingRule::SimpleMajority as u8
+                | 1 << CountingRule::Supermajority as u8,
+            allow_secret_ballot: true,
+            min_reveal_timeout: 5,
+        }
+    }
+
+    #[test]
+    fn test_policy_for_picks_the_cost_band() {
+        let mut vote_policies = VotePolicies::new();
+        vote_policies.set(ProposalType::Grant, U256::from(0), policy(10));
+        vote_policies.set(ProposalType::Grant, U256::from(1000), policy(20));
+        assert_eq!(
+            vote_policies.policy_for(ProposalType::Grant, U256::from(999)),
+            Some(policy(10))
+        );
+        assert_eq!(
+            vote_policies.policy_for(ProposalType::Grant, U256::from(1000)),
+            Some(policy(20))
+        );
+        assert_eq!(
+            vote_policies.policy_for(ProposalType::Grant, U256::MAX),
+            Some(policy(20))
+        );
+        assert_eq!(
+            vote_policies.policy_for(ProposalType::Governance, U256::from(0)),
+            None
+        );
+        assert_eq!(
+            VotePolicies::deserialize(vote_policies.serialize()),
+            vote_policies
+        );
+    }
+
+    #[test]
+    fn test_vote_configuration_bounds() {
+        let policy: VotePolicy = policy(10);
+        assert_eq!(
+            policy.check_member_quorum(9),
+            Err(ProposalError::QuorumBelowPolicy)
+        );
+        assert_eq!(policy.check_member_quorum(10), Ok(()));
+        assert_eq!(
+            policy.check_threshold(49),
+            Err(ProposalError::ThresholdBelowPolicy)
+        );
+        assert_eq!(
+            policy.check_duration(101),
+            Err(ProposalError::DurationOutsidePolicy)
+        );
+        assert_eq!(
+            policy.check_duration(9),
+            Err(ProposalError::DurationOutsidePolicy)
+        );
+        assert_eq!(policy.check_duration(100), Ok(()));
+    }
+
+    #[test]
+    fn test_staking_limits_counting_rule_and_secret_ballot_bounds() {
+        let policy: VotePolicy = policy(10);
+        let staking_limits = |balance_share: u64, total_stake_share: Option<u64>| StakingLimits {
+            balance_share,
+            total_stake_share,
+        };
+        assert_eq!(
+            policy.check_staking_limits(staking_limits(999, None)),
+            Err(ProposalError::StakingLimitsBelowPolicy)
+        );
+        assert_eq!(
+            policy.check_staking_limits(staking_limits(1000, Some(499))),
+            Err(ProposalError::StakingLimitsBelowPolicy)
+        );
+        assert_eq!(
+            policy.check_staking_limits(staking_limits(1000, None)),
+            Ok(())
+        );
+        // The DAO can require a total stake cap and bound both caps from above
+        let capped: VotePolicy = VotePolicy {
+            max_balance_share: 5000,
+            max_total_stake_share: 2000,
+            ..policy
+        };
+        assert_eq!(
+            capped.check_staking_limits(staking_limits(5001, Some(2000))),
+            Err(ProposalError::StakingLimitsAbovePolicy)
+        );
+        assert_eq!(
+            capped.check_staking_limits(staking_limits(5000, Some(2001))),
+            Err(ProposalError::StakingLimitsAbovePolicy)
+        );
+        assert_eq!(
+            capped.check_staking_limits(staking_limits(5000, None)),
+            Err(ProposalError::StakingLimitsAbovePolicy)
+        );
+        assert_eq!(
+            capped.check_staking_limits(staking_limits(5000, Some(2000))),
+            Ok(())
+        );
+
+        assert_eq!(
+            policy.check_counting_rule(CountingRule::Supermajority),
+            Ok(())
+        );
+        assert_eq!(
+            policy.check_counting_rule(CountingRule::QuadraticWeighting),
+            Err(ProposalError::CountingRuleNotAllowed)
+        );
+
+        let secret_ballot = |reveal_timeout: u64| {
+            Some(SecretBallotConfiguration {
+                reveal_timeout,
+                unrevealed_policy: UnrevealedPolicy::Refund,
+            })
+        };
+        assert_eq!(policy.check_secret_ballot(None), Ok(()));
+        assert_eq!(policy.check_secret_ballot(secret_ballot(5)), Ok(()));
+        assert_eq!(
+            policy.check_secret_ballot(secret_ballot(4)),
+            Err(ProposalError::SecretBallotOutsidePolicy)
+        );
+        let public_only: VotePolicy = VotePolicy {
+            allow_secret_ballot: false,
+            ..policy
+        };
+        assert_eq!(
+            public_only.check_secret_ballot(secret_ballot(5)),
+            Err(ProposalError::SecretBallotOutsidePolicy)
+        );
+    }
+
+    #[test]
+    fn test_serialization() {
+        assert_eq!(VotePolicy::deserialize(policy(10).serialize()), policy(10));
+    }
+}
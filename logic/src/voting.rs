@@ -2,26 +2,69 @@
 extern crate alloc;
 use crate::{
     custom_types::custom_types::{
-        GovernanceProposalSerialized, ProposalSerialized, VotersSerialized, VotingSerialized,
+        CommitmentsSerialized, GovernanceProposalSerialized, LateSwingRuleSerialized,
+        ProposalSerialized, SecretBallotSerialized, TransitionVoteSerialized, VotersSerialized,
+        VotingSerialized,
     },
     error::*,
-    proposal::{ProposalStatus, ProposalType},
-    GovernanceProposal, Proposal,
+    fixed_point::{
+        basis_points_of, mul_div, percentage_of_rounded_up, percentage_to_basis_points, pro_rata,
+    },
+    proposal::{
+        ProposalStatus, ProposalType, SecretBallotConfiguration, StakingLimits, UnrevealedPolicy,
+    },
+    vote_counting::{CountingRule, VoteCountingRule},
+    vote_outcome::VoteOutcomeReport,
+    GovernanceAction, GovernanceActionResult, GovernanceExecution, GovernanceProposal, Proposal,
 };
 use alloc::collections::BTreeMap;
-use alloc::string::String;
 use alloc::vec::Vec;
+use blake2::{
+    digest::{Update, VariableOutput},
+    VarBlake2b,
+};
 use core::{
     cmp::{Eq, Ord, PartialEq, PartialOrd},
     ops::Add,
 };
-use types::{account::AccountHash, bytesrepr::FromBytes, ContractHash, PublicKey, U256};
+use types::{
+    account::AccountHash,
+    bytesrepr::{FromBytes, ToBytes},
+    ContractHash, PublicKey, U256,
+};
+
+// Reputation to lock, release or pay out per voter
+type VoterReputation = Vec<(AccountHash, U256)>;
 
 #[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone)]
 pub struct VotingData {
     pub reputation_staked: U256,
-    pub vote: bool,
+    pub vote: VoteDirection,
     pub claimed: bool,
+    // Set when the ballot was cast by the voter's delegate
+    pub delegate: Option<AccountHash>,
+}
+// Abstaining stake counts toward the quorums but not toward the threshold
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone, Copy)]
+pub enum VoteDirection {
+    For,
+    Against,
+    Abstain,
+}
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone)]
+pub struct Commitment {
+    pub commitment: [u8; 32],
+    pub reputation_committed: U256,
+    pub revealed: bool,
+    pub settled: bool,
+}
+// Anti-sniping: a ballot in the final `window` seconds that changes the leading side pushes
+// the end of the vote back by `extension` seconds, at most `max_extensions` times
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone, Copy, Default)]
+pub struct LateSwingRule {
+    pub window: u64,
+    pub extension: u64,
+    pub max_extensions: u64,
 }
 #[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone, Copy)]
 pub enum VoteResult {
@@ -45,39 +88,120 @@ pub struct Voting {
     pub for_votes: U256,
     pub against_votes: U256,
     pub input_reputation: U256,
+    // Rewards paid to the winners, who keep their locked stake. What the reward pool keeps
+    // after every winner claimed is rounding dust for the DAO account
+    pub distributed_rewards: U256,
+    // Losing and forfeited stakes that couldn't be debited, they are left out of the pool
+    pub uncollected_stakes: U256,
+    pub dust_collected: bool,
     pub for_voters: BTreeMap<AccountHash, VotingData>,
     pub against_voters: BTreeMap<AccountHash, VotingData>,
+    pub abstain_votes: U256,
+    pub abstain_voters: BTreeMap<AccountHash, VotingData>,
+    pub proposer_stake_reclaimed: bool,
     pub result: VoteResult,
+    // Informal transition vote that precedes a governance full vote
+    pub transition_for_votes: U256,
+    pub transition_against_votes: U256,
+    pub transition_voters: BTreeMap<AccountHash, VotingData>,
+    pub full_vote_start_timestamp: u64,
+    // Commit-reveal secret ballots
+    pub commitments: BTreeMap<AccountHash, Commitment>,
+    pub forfeited_reputation: U256,
+    // Effective end of the current vote, late swings push it back
+    pub end_timestamp: u64,
+    pub late_swing_rule: LateSwingRule,
+    pub extensions: u64,
+    pub leading_side: Option<bool>,
+    // Voting power is read from the reputation checkpoints at this time, members and
+    // reputation supply are taken from the same snapshot
+    pub snapshot_timestamp: u64,
+    pub eligible_members: u64,
+    pub eligible_reputation: U256,
+    // Set once the full vote is closed
+    pub outcome_report: Option<VoteOutcomeReport>,
+    // Governance proposals only
+    pub governance_execution: Option<GovernanceExecution>,
+}
+
+impl LateSwingRule {
+    pub fn serialize(&self) -> LateSwingRuleSerialized {
+        (self.window, self.extension, self.max_extensions)
+    }
+
+    pub fn deserialize(serialized_late_swing_rule: LateSwingRuleSerialized) -> LateSwingRule {
+        LateSwingRule {
+            window: serialized_late_swing_rule.0,
+            extension: serialized_late_swing_rule.1,
+            max_extensions: serialized_late_swing_rule.2,
+        }
+    }
 }
 
 impl Voting {
     pub fn new(
         start_timestamp: u64,
         serialized_proposal: ProposalSerialized,
+        late_swing_rule: LateSwingRule,
+        eligible_members: u64,
+        eligible_reputation: U256,
     ) -> Result<Voting, VotingEngineError> {
+        let proposal: Proposal = Proposal::deserialize(serialized_proposal);
         Ok(Voting {
             start_timestamp,
             against_voters: BTreeMap::new(),
             for_voters: BTreeMap::new(),
+            abstain_voters: BTreeMap::new(),
+            abstain_votes: U256::from(0),
+            proposer_stake_reclaimed: false,
             against_votes: U256::from(0),
             for_votes: U256::from(0),
             total_members: 0 as u64,
             total_staked_reputation: U256::from(0),
             result: VoteResult::InVote,
-            proposal: Some(Proposal::deserialize(serialized_proposal)),
+            end_timestamp: proposal.vote_configuration.timeout,
+            proposal: Some(proposal),
             input_reputation: U256::from(0),
             governance_proposal: None,
             proposal_type: ProposalType::Grant,
+            transition_for_votes: U256::from(0),
+            transition_against_votes: U256::from(0),
+            transition_voters: BTreeMap::new(),
+            full_vote_start_timestamp: start_timestamp,
+            commitments: BTreeMap::new(),
+            forfeited_reputation: U256::from(0),
+            late_swing_rule,
+            extensions: 0,
+            leading_side: None,
+            snapshot_timestamp: start_timestamp,
+            eligible_members,
+            eligible_reputation,
+            outcome_report: None,
+            distributed_rewards: U256::from(0),
+            uncollected_stakes: U256::from(0),
+            dust_collected: false,
+            governance_execution: None,
         })
     }
     pub fn new_governance(
         start_timestamp: u64,
         serialized_governance_proposal: GovernanceProposalSerialized,
+        late_swing_rule: LateSwingRule,
+        eligible_members: u64,
+        eligible_reputation: U256,
+        execution_delay: u64,
     ) -> Result<Voting, VotingEngineError> {
+        let mut governance_proposal: GovernanceProposal =
+            GovernanceProposal::deserialize(serialized_governance_proposal);
+        // Every governance vote opens with the informal transition vote
+        governance_proposal.proposal_status = ProposalStatus::WaitingFullVote;
         Ok(Voting {
             start_timestamp,
             against_voters: BTreeMap::new(),
             for_voters: BTreeMap::new(),
+            abstain_voters: BTreeMap::new(),
+            abstain_votes: U256::from(0),
+            proposer_stake_reclaimed: false,
             against_votes: U256::from(0),
             for_votes: U256::from(0),
             total_members: 0 as u64,
@@ -85,27 +209,132 @@ impl Voting {
             result: VoteResult::InVote,
             proposal: None,
             input_reputation: U256::from(0),
-            governance_proposal: Some(GovernanceProposal::deserialize(
-                serialized_governance_proposal,
-            )),
+            // Transition vote
+            end_timestamp: governance_proposal.vote_configuration.timeout,
+            governance_proposal: Some(governance_proposal),
             proposal_type: ProposalType::Governance,
+            transition_for_votes: U256::from(0),
+            transition_against_votes: U256::from(0),
+            transition_voters: BTreeMap::new(),
+            full_vote_start_timestamp: 0,
+            commitments: BTreeMap::new(),
+            forfeited_reputation: U256::from(0),
+            late_swing_rule,
+            extensions: 0,
+            leading_side: None,
+            snapshot_timestamp: start_timestamp,
+            eligible_members,
+            eligible_reputation,
+            outcome_report: None,
+            distributed_rewards: U256::from(0),
+            uncollected_stakes: U256::from(0),
+            dust_collected: false,
+            governance_execution: Some(GovernanceExecution::new(execution_delay)),
         })
     }
 
+    pub fn snapshot_at(&self) -> u64 {
+        self.snapshot_timestamp
+    }
+
     pub fn start_at(&self) -> u64 {
         self.start_timestamp
     }
 
     pub fn end_at(&self) -> u64 {
+        self.end_timestamp
+    }
+
+    pub fn secret_ballot(&self) -> Option<SecretBallotConfiguration> {
         if self.proposal_type == ProposalType::Grant {
-            self.proposal.clone().unwrap().vote_configuration.timeout
+            self.proposal
+                .as_ref()
+                .unwrap()
+                .vote_configuration
+                .secret_ballot
         } else {
             self.governance_proposal
-                .clone()
+                .as_ref()
                 .unwrap()
                 .vote_configuration
-                .timeout
+                .secret_ballot
+        }
+    }
+
+    // Secret ballots can be revealed after the vote ends, the outcome is only known once
+    // the reveal window closes
+    pub fn reveal_end_at(&self) -> u64 {
+        if self.proposal_type == ProposalType::Governance
+            && self.governance_proposal.as_ref().unwrap().proposal_status
+                == ProposalStatus::WaitingFullVote
+        {
+            // Transition votes are never secret
+            return self.end_at();
+        }
+        match self.secret_ballot() {
+            Some(secret_ballot) => self.end_at() + secret_ballot.reveal_timeout,
+            None => self.end_at(),
+        }
+    }
+
+    // A full vote can be finalized before its timeout once the result is the same whichever
+    // side the members who haven't voted yet join with all the reputation left. Ballots can't be
+    // changed or withdrawn from then on, so the outcome stays locked
+    pub fn is_outcome_locked(&self) -> bool {
+        if self.secret_ballot().is_some() {
+            // Tallies are unknown until the ballots are revealed
+            return false;
         }
+        let (proposal_status, member_quorum, reputation_quorum, threshold, counting_rule): (
+            ProposalStatus,
+            u64,
+            u64,
+            u64,
+            CountingRule,
+        ) = if self.proposal_type == ProposalType::Grant {
+            let proposal: &Proposal = self.proposal.as_ref().unwrap();
+            (
+                proposal.proposal_status,
+                proposal.vote_configuration.member_quorum,
+                proposal.vote_configuration.reputation_quorum,
+                proposal.vote_configuration.threshold.into(),
+                proposal.vote_configuration.counting_rule,
+            )
+        } else {
+            let governance_proposal: &GovernanceProposal =
+                self.governance_proposal.as_ref().unwrap();
+            (
+                governance_proposal.proposal_status,
+                0,
+                governance_proposal.vote_configuration.full_vote_quorum,
+                governance_proposal.vote_configuration.full_vote_threshold,
+                governance_proposal.vote_configuration.counting_rule,
+            )
+        };
+        if proposal_status != ProposalStatus::InFullVote
+            || self.total_members < self.member_quorum(member_quorum)
+            || self.total_staked_reputation < self.reputation_quorum(reputation_quorum)
+        {
+            return false;
+        }
+        let counting_rule: &dyn VoteCountingRule = counting_rule.rule();
+        let (for_weight, against_weight) =
+            counting_rule.tally(&mut self.for_voters.values().chain(self.against_voters.values()));
+        if self.total_members >= self.eligible_members {
+            // Everyone voted
+            return true;
+        }
+        let remaining_members: u64 = self.eligible_members - self.total_members;
+        let remaining_reputation: U256 = if self.eligible_reputation > self.total_staked_reputation
+        {
+            self.eligible_reputation - self.total_staked_reputation
+        } else {
+            U256::from(0)
+        };
+        let remaining_weight: U256 =
+            counting_rule.max_weight(remaining_reputation, remaining_members);
+        counting_rule.count(for_weight + remaining_weight, against_weight, threshold)
+            == counting_rule.count(for_weight, against_weight + remaining_weight, threshold)
     }
 
     pub fn calculate_vote_outcome(
@@ -115,89 +344,217 @@ impl Voting {
     ) -> Result<VoteResult, VotingEngineError> {
         // Check that voting has ended
         let proposal: Proposal = self.proposal.clone().unwrap();
-        let timeout: u64 = proposal.vote_configuration.timeout;
-        if timeout > current_time {
+        let timeout: u64 = self.reveal_end_at();
+        if timeout > current_time && !self.is_outcome_locked() {
             return Err(VotingEngineError::VotingOngoing);
         }
         if proposal.proposal_status != ProposalStatus::InFullVote {
             return Err(VotingEngineError::VotingNotOngoing);
         }
-        if self.total_members < proposal.vote_configuration.member_quorum {
-            self.result = VoteResult::MemberQuorumUnmet;
-        }
-        if self.total_staked_reputation < proposal.vote_configuration.reputation_quorum {
-            self.result = VoteResult::ReputationQuorumUnmet;
-        }
-        let total_votes = self.for_votes + self.against_votes;
-        if self.for_votes > self.against_votes {
-            let percentage: U256 = (self.for_votes * 10000) / (U256::from(100) * (total_votes));
-            if percentage <= proposal.vote_configuration.threshold.into() {
-                self.result = VoteResult::PassThresholdUnmet;
-            } else {
-                if self.proposal_type == ProposalType::Grant {
-                    // calculate input reputation
-                    let denominator =
-                        (U256::from(10).pow(U256::from(12))) / reputation_allocation_ratio;
-                    let input_reputation =
-                        (proposal.cost * U256::from(10).pow(U256::from(16))) / denominator; // 8 decimals
-                    self.input_reputation = input_reputation;
-                }
-                self.result = VoteResult::Approved;
-            }
-        } else if self.against_votes >= self.for_votes {
-            let percentage: U256 = (self.against_votes * 10000) / (U256::from(100) * (total_votes));
-            if percentage <= proposal.vote_configuration.threshold.into() {
-                self.result = VoteResult::FailThresholdUnmet;
-            } else {
-                self.result = VoteResult::Rejected;
-            }
+        let mut outcome_report: VoteOutcomeReport = self.resolve_outcome(
+            proposal.vote_configuration.member_quorum,
+            proposal.vote_configuration.reputation_quorum,
+            proposal.vote_configuration.threshold.into(),
+            proposal.vote_configuration.counting_rule,
+        );
+        if outcome_report.result == VoteResult::Approved
+            && self.proposal_type == ProposalType::Grant
+        {
+            // calculate input reputation, the allocation ratio has 12 decimals
+            let input_reputation: U256 = mul_div(
+                proposal.cost * U256::from(10).pow(U256::from(16)),
+                U256::from(reputation_allocation_ratio),
+                U256::from(10).pow(U256::from(12)),
+            ); // 8 decimals
+            self.input_reputation = input_reputation;
+            outcome_report.input_reputation = input_reputation;
         }
+        self.result = outcome_report.result;
+        self.outcome_report = Some(outcome_report);
+        self.proposal.as_mut().unwrap().proposal_status = ProposalStatus::FullVoteComplete;
         Ok(self.result)
     }
+    // Approved actions are queued behind the timelock, they are carried out by
+    // `execute_governance_actions`
     pub fn calculate_governance_vote_outcome(
         &mut self,
         current_time: u64,
-    ) -> Result<(VoteResult, (bool, (String, String))), VotingEngineError> {
+    ) -> Result<VoteResult, VotingEngineError> {
         // Check that voting has ended
         let proposal: GovernanceProposal = self.governance_proposal.clone().unwrap();
-        let timeout: u64 = proposal.vote_configuration.timeout;
-        let mut executed: bool = false;
-        if timeout > current_time {
+        let timeout: u64 = self.reveal_end_at();
+        if timeout > current_time && !self.is_outcome_locked() {
             return Err(VotingEngineError::VotingOngoing);
         }
+        if proposal.proposal_status == ProposalStatus::WaitingFullVote {
+            self.calculate_transition_vote_outcome(current_time);
+            return Ok(self.result);
+        }
         if proposal.proposal_status != ProposalStatus::InFullVote {
             return Err(VotingEngineError::VotingNotOngoing);
         }
-        if self.total_staked_reputation < proposal.vote_configuration.full_vote_quorum {
-            self.result = VoteResult::ReputationQuorumUnmet;
+        // Governance votes have no member quorum
+        let mut outcome_report: VoteOutcomeReport = self.resolve_outcome(
+            0,
+            proposal.vote_configuration.full_vote_quorum,
+            proposal.vote_configuration.full_vote_threshold,
+            proposal.vote_configuration.counting_rule,
+        );
+        if outcome_report.result == VoteResult::Approved {
+            outcome_report.action_results = proposal
+                .governance_actions
+                .iter()
+                .map(|_| GovernanceActionResult::Pending)
+                .collect();
+            self.governance_execution
+                .as_mut()
+                .unwrap()
+                .queue(current_time);
         }
-        let total_votes = self.for_votes + self.against_votes;
-        if self.for_votes > self.against_votes {
-            let percentage: U256 = (self.for_votes * 10000) / (U256::from(100) * (total_votes));
-            if percentage <= proposal.vote_configuration.full_vote_threshold.into() {
-                self.result = VoteResult::PassThresholdUnmet;
-            } else {
-                // Vote passed
-                if self.result == VoteResult::Approved {
-                    executed = true;
-                }
-                self.result = VoteResult::Approved;
-            }
-        } else if self.against_votes >= self.for_votes {
-            let percentage: U256 = (self.against_votes * 10000) / (U256::from(100) * (total_votes));
-            if percentage <= proposal.vote_configuration.full_vote_threshold.into() {
-                self.result = VoteResult::FailThresholdUnmet;
-            } else {
-                self.result = VoteResult::Rejected;
+        self.result = outcome_report.result;
+        self.outcome_report = Some(outcome_report);
+        self.governance_proposal.as_mut().unwrap().proposal_status =
+            ProposalStatus::FullVoteComplete;
+        Ok(self.result)
+    }
+
+    // Actions of an approved governance proposal whose timelock has expired, they can only be
+    // taken once
+    pub fn execute_governance_actions(
+        &mut self,
+        current_time: u64,
+    ) -> Result<Vec<GovernanceAction>, VotingEngineError> {
+        self.governance_execution
+            .as_mut()
+            .ok_or(VotingEngineError::ExecutionNotQueued)?
+            .execute(current_time)?;
+        Ok(self
+            .governance_proposal
+            .as_ref()
+            .unwrap()
+            .governance_actions
+            .clone())
+    }
+
+    pub fn veto_governance_actions(
+        &mut self,
+        vetoer: AccountHash,
+        current_time: u64,
+    ) -> Result<(), VotingEngineError> {
+        self.governance_execution
+            .as_mut()
+            .ok_or(VotingEngineError::ExecutionNotQueued)?
+            .veto(vetoer, current_time)
+    }
+
+    // Every criterion is evaluated, an unmet quorum is final whatever the tally
+    fn resolve_outcome(
+        &self,
+        member_quorum: u64,
+        reputation_quorum: u64,
+        threshold: u64,
+        counting_rule: CountingRule,
+    ) -> VoteOutcomeReport {
+        let counting_rule: &dyn VoteCountingRule = counting_rule.rule();
+        let (for_weight, against_weight) =
+            counting_rule.tally(&mut self.for_voters.values().chain(self.against_voters.values()));
+        let member_quorum_met: bool = self.total_members >= self.member_quorum(member_quorum);
+        let reputation_quorum_met: bool =
+            self.total_staked_reputation >= self.reputation_quorum(reputation_quorum);
+        let threshold_result: VoteResult =
+            counting_rule.count(for_weight, against_weight, threshold);
+        let result: VoteResult = if !member_quorum_met {
+            VoteResult::MemberQuorumUnmet
+        } else if !reputation_quorum_met {
+            VoteResult::ReputationQuorumUnmet
+        } else {
+            threshold_result
+        };
+        VoteOutcomeReport {
+            result,
+            member_quorum_met,
+            reputation_quorum_met,
+            threshold_met: threshold_result == VoteResult::Approved
+                || threshold_result == VoteResult::Rejected,
+            total_members: self.total_members,
+            eligible_members: self.eligible_members,
+            for_votes: self.for_votes,
+            against_votes: self.against_votes,
+            abstain_votes: self.abstain_votes,
+            member_participation: VoteOutcomeReport::percentage(
+                U256::from(self.total_members),
+                U256::from(self.eligible_members),
+            ),
+            reputation_participation: VoteOutcomeReport::percentage(
+                self.total_staked_reputation,
+                self.eligible_reputation,
+            ),
+            for_percentage: VoteOutcomeReport::percentage(for_weight, for_weight + against_weight),
+            input_reputation: U256::from(0),
+            action_results: Vec::new(),
+        }
+    }
+
+    // Records what became of the actions of an approved governance proposal
+    pub fn record_action_results(&mut self, action_results: Vec<GovernanceActionResult>) {
+        if let Some(outcome_report) = self.outcome_report.as_mut() {
+            outcome_report.action_results = action_results;
+        }
+    }
+
+    // Closes the informal transition vote, the proposal moves to a full vote only if
+    // the transition quorum and threshold are met
+    fn calculate_transition_vote_outcome(&mut self, current_time: u64) {
+        let vote_configuration = self.governance_proposal.clone().unwrap().vote_configuration;
+        let transition_abstain_votes: U256 = self
+            .transition_voters
+            .values()
+            .filter(|voting_data| voting_data.vote == VoteDirection::Abstain)
+            .fold(U256::from(0), |total, voting_data| {
+                total + voting_data.reputation_staked
+            });
+        let total_votes =
+            self.transition_for_votes + self.transition_against_votes + transition_abstain_votes;
+        if total_votes == U256::from(0)
+            || total_votes < self.reputation_quorum(vote_configuration.transition_vote_quorum)
+        {
+            self.result = VoteResult::ReputationQuorumUnmet;
+        } else {
+            let counting_rule: &dyn VoteCountingRule = vote_configuration.counting_rule.rule();
+            let (for_weight, against_weight) =
+                counting_rule.tally(&mut self.transition_voters.values());
+            let outcome: VoteResult = counting_rule.count(
+                for_weight,
+                against_weight,
+                vote_configuration.transition_vote_threshold,
+            );
+            if outcome == VoteResult::Approved {
+                // Transition vote passed, the full vote starts now
+                self.full_vote_start_timestamp = current_time;
+                self.end_timestamp = current_time + vote_configuration.full_vote_timeout;
+                self.governance_proposal.as_mut().unwrap().proposal_status =
+                    ProposalStatus::InFullVote;
+                self.result = VoteResult::InVote;
+                return;
             }
+            self.result = outcome;
         }
-        Ok((self.result, (executed, proposal.new_variable_key_value)))
+        self.governance_proposal.as_mut().unwrap().proposal_status =
+            ProposalStatus::FullVoteComplete;
     }
 
     pub fn claim_reputation(&mut self, caller: AccountHash) -> Result<U256, VotingEngineError> {
-        if self.result != VoteResult::Approved || self.result != VoteResult::Rejected {
+        if self.result != VoteResult::Approved && self.result != VoteResult::Rejected {
             return Err(VotingEngineError::VoteFailed);
         }
+        if let Some(voting_data) = self.abstain_voters.get_mut(&caller) {
+            // Abstentions keep their stake, with no reward or loss
+            if voting_data.claimed {
+                return Err(VotingEngineError::ReputationAlreadyClaimed);
+            }
+            voting_data.claimed = true;
+            return Ok(U256::from(0));
+        }
         let is_for_voter = self.for_voters.contains_key(&caller);
         let is_against_voter = self.against_voters.contains_key(&caller);
         let mut caller_voting_data: Option<&mut VotingData> = None;
@@ -212,43 +569,39 @@ impl Voting {
                     return Err(VotingEngineError::ReputationAlreadyClaimed);
                 }
                 voting_data.claimed = true;
-                let mut similar_votes = self.for_votes;
-                let mut opposite_votes = self.against_votes;
                 let vote_rejected: bool = self.result == VoteResult::Rejected;
-                if vote_rejected {
-                    similar_votes = self.against_votes;
-                    opposite_votes = self.for_votes;
+                if vote_rejected != is_against_voter {
+                    // Losing stakes are shared among the winners
+                    return Ok(U256::from(0));
                 }
-                let voter_shares =
-                    (voting_data.reputation_staked * 1000) / (U256::from(10) * (similar_votes));
-                let mut rep_gained: U256 = (voter_shares * (opposite_votes)) / 100;
-                if self.proposal_type == ProposalType::Grant {
-                    if !vote_rejected {
-                        // If vote was approved, distribute input reputation
-                        let nominator = self.input_reputation;
-                        if caller == self.proposal.clone().unwrap().proposer {
-                            // Give OP 1-Policing Ratio rep
-
-                            // Op ratio = 1-Policing Ratio
-                            let op_ratio = U256::from(10000)
-                                - U256::from(
-                                    self.proposal.clone().unwrap().ratios.policing_ratio * 100,
-                                );
-                            let denominator: U256 = (U256::from(10).pow(U256::from(12)))
-                                / (U256::from(10000) / op_ratio);
-                            rep_gained += nominator / denominator;
-                        } else {
-                            // NOT OP, gets pro rata policing ratio
-                            let denominator: U256 = (U256::from(10).pow(U256::from(12)))
-                                / (U256::from(10000)
-                                    / self.proposal.clone().unwrap().ratios.policing_ratio
-                                    * 100);
-                            rep_gained = (nominator / denominator)
-                                / (U256::from(10000) / (voter_shares * 100));
-                        }
+                let reputation_staked: U256 = voting_data.reputation_staked;
+                let (similar_votes, opposite_votes) = if vote_rejected {
+                    (self.against_votes, self.for_votes)
+                } else {
+                    (self.for_votes, self.against_votes)
+                };
+                // Forfeited secret ballots are shared like the opposite side's stake
+                let mut rep_gained: U256 = pro_rata(
+                    (opposite_votes + self.forfeited_reputation)
+                        .saturating_sub(self.uncollected_stakes),
+                    reputation_staked,
+                    similar_votes,
+                );
+                if self.proposal_type == ProposalType::Grant && !vote_rejected {
+                    // If vote was approved, distribute input reputation: the policing ratio goes
+                    // pro rata to the voters who backed it, the rest to OP
+                    let proposal: &Proposal = self.proposal.as_ref().unwrap();
+                    let policing_reputation: U256 = basis_points_of(
+                        self.input_reputation,
+                        percentage_to_basis_points(proposal.ratios.policing_ratio),
+                    );
+                    rep_gained += pro_rata(policing_reputation, reputation_staked, similar_votes);
+                    if caller == proposal.proposer {
+                        rep_gained += self.input_reputation - policing_reputation;
                     }
                 }
-                Ok(rep_gained + voting_data.reputation_staked)
+                self.distributed_rewards += rep_gained;
+                Ok(rep_gained)
             }
             None => Err(VotingEngineError::NoReputationToClaim),
         }
@@ -256,11 +609,7 @@ impl Voting {
 
     // If vote fails, users call this function to get their stakes back
     pub fn get_stake(&mut self, caller: AccountHash) -> Result<U256, VotingEngineError> {
-        if self.result != VoteResult::FailCriteriaUnmet
-            || self.result != VoteResult::MemberQuorumUnmet
-            || self.result != VoteResult::FailThresholdUnmet
-            || self.result != VoteResult::ReputationQuorumUnmet
-        {
+        if !self.vote_failed() {
             return Err(VotingEngineError::VoteDidNotFail);
         }
         let is_for_voter = self.for_voters.contains_key(&caller);
@@ -270,6 +619,8 @@ impl Voting {
             caller_voting_data = self.against_voters.get_mut(&caller);
         } else if is_for_voter {
             caller_voting_data = self.for_voters.get_mut(&caller);
+        } else {
+            caller_voting_data = self.abstain_voters.get_mut(&caller);
         }
         match caller_voting_data {
             Some(voting_data) => {
@@ -283,6 +634,144 @@ impl Voting {
         }
     }
 
+    // The proposer stake is returned when the vote failed, as for voters
+    pub fn get_proposer_stake(&mut self, caller: AccountHash) -> Result<U256, VotingEngineError> {
+        if !self.vote_failed() {
+            return Err(VotingEngineError::VoteDidNotFail);
+        }
+        let (proposer, staked_rep): (AccountHash, U256) =
+            if self.proposal_type == ProposalType::Governance {
+                let governance_proposal: &GovernanceProposal =
+                    self.governance_proposal.as_ref().unwrap();
+                (governance_proposal.proposer, governance_proposal.staked_rep)
+            } else {
+                let proposal: &Proposal = self.proposal.as_ref().unwrap();
+                (proposal.proposer, proposal.staked_rep)
+            };
+        if caller != proposer {
+            return Err(VotingEngineError::NoReputationToClaim);
+        }
+        if self.proposer_stake_reclaimed {
+            return Err(VotingEngineError::ReputationAlreadyClaimed);
+        }
+        self.proposer_stake_reclaimed = true;
+        Ok(staked_rep)
+    }
+
+    // Everything `caller` can take from a closed vote, claims that would fail count as nothing:
+    // (reputation gained, stake refunded, proposer stake refunded)
+    pub fn claim_all(&mut self, caller: AccountHash) -> (U256, U256, U256) {
+        if self.vote_failed() {
+            (
+                U256::from(0),
+                self.get_stake(caller).unwrap_or(U256::from(0)),
+                self.get_proposer_stake(caller).unwrap_or(U256::from(0)),
+            )
+        } else {
+            (
+                self.claim_reputation(caller).unwrap_or(U256::from(0)),
+                U256::from(0),
+                U256::from(0),
+            )
+        }
+    }
+
+    // Push settlement: pays the next `page_size` voters who haven't claimed yet under the
+    // claim rules, so pull claims and pages never pay twice. The proposer stake of a failed
+    // vote is refunded with the last page. Returns the transfers owed and whether the vote is
+    // fully settled
+    pub fn settle_page(
+        &mut self,
+        page_size: u64,
+    ) -> Result<(Vec<(AccountHash, U256)>, bool), VotingEngineError> {
+        if self.result == VoteResult::InVote {
+            return Err(VotingEngineError::VotingOngoing);
+        }
+        let unclaimed_voters: Vec<AccountHash> = self
+            .for_voters
+            .iter()
+            .chain(self.against_voters.iter())
+            .chain(self.abstain_voters.iter())
+            .filter(|(_, voting_data)| !voting_data.claimed)
+            .map(|(voter, _)| *voter)
+            .collect();
+        let mut payouts: Vec<(AccountHash, U256)> = Vec::new();
+        for voter in unclaimed_voters.iter().take(page_size as usize) {
+            // Refunded voter stakes were only locked, nothing is transferred for them
+            let (gained_reputation, _, proposer_stake) = self.claim_all(*voter);
+            payouts.push((*voter, gained_reputation + proposer_stake));
+        }
+        let settled: bool = unclaimed_voters.len() as u64 <= page_size;
+        if settled && self.vote_failed() && !self.proposer_stake_reclaimed {
+            let proposer: AccountHash = if self.proposal_type == ProposalType::Governance {
+                self.governance_proposal.as_ref().unwrap().proposer
+            } else {
+                self.proposal.as_ref().unwrap().proposer
+            };
+            let proposer_stake: U256 = self.get_proposer_stake(proposer)?;
+            payouts.push((proposer, proposer_stake));
+        }
+        Ok((payouts, settled))
+    }
+
+    // Reward pool left over once every winning voter has claimed, the floor rounding of the
+    // pro rata shares and an input reputation nobody could claim end up here. A failed vote has
+    // no winners, so the stakes forfeited by unrevealed secret ballots are left over as soon as
+    // it closes. Taken only once
+    pub fn take_rounding_dust(&mut self) -> U256 {
+        if self.dust_collected {
+            return U256::from(0);
+        }
+        let (winning_voters, reward_pool): (Option<&BTreeMap<AccountHash, VotingData>>, U256) =
+            match self.result {
+                VoteResult::Approved => (Some(&self.for_voters), self.against_votes),
+                VoteResult::Rejected => (Some(&self.against_voters), self.for_votes),
+                VoteResult::InVote => return U256::from(0),
+                _ => (None, U256::from(0)),
+            };
+        if let Some(winning_voters) = winning_voters {
+            if winning_voters
+                .values()
+                .any(|voting_data| !voting_data.claimed)
+            {
+                return U256::from(0);
+            }
+        }
+        self.dust_collected = true;
+        (reward_pool + self.forfeited_reputation + self.input_reputation)
+            .saturating_sub(self.uncollected_stakes + self.distributed_rewards)
+    }
+
+    // The voting engine debits losing and forfeited stakes into the reward pool when the vote
+    // closes, a voter's balance may have fallen below the stake in the meantime
+    pub fn record_uncollected_stake(&mut self, uncollected_stake: U256) {
+        self.uncollected_stakes += uncollected_stake;
+    }
+
+    // Voters on the losing side of a closed vote, with their stake, they have nothing to claim
+    pub fn losing_voters(&self) -> Vec<(AccountHash, U256)> {
+        let losing_voters: &BTreeMap<AccountHash, VotingData> = match self.result {
+            VoteResult::Approved => &self.against_voters,
+            VoteResult::Rejected => &self.for_voters,
+            _ => return Vec::new(),
+        };
+        losing_voters
+            .iter()
+            .map(|(voter, voting_data)| (*voter, voting_data.reputation_staked))
+            .collect()
+    }
+
+    fn vote_failed(&self) -> bool {
+        matches!(
+            self.result,
+            VoteResult::FailCriteriaUnmet
+                | VoteResult::MemberQuorumUnmet
+                | VoteResult::ReputationQuorumUnmet
+                | VoteResult::PassThresholdUnmet
+                | VoteResult::FailThresholdUnmet
+        )
+    }
+
     pub fn cast_vote(
         &mut self,
         caller: AccountHash,
@@ -290,72 +779,561 @@ impl Voting {
         reputation_balance: U256,
         reputation_to_stake: U256,
         committed_reputation: U256,
-        vote_direction: bool,
-    ) -> Result<(), VotingEngineError> {
-        let is_for_voter = self.for_voters.contains_key(&caller);
-        let is_against_voter = self.against_voters.contains_key(&caller);
-        if is_against_voter || is_for_voter {
-            return Err(VotingEngineError::AlreadyVoted);
+        vote_direction: VoteDirection,
+    ) -> Result<U256, VotingEngineError> {
+        if self.proposal_type == ProposalType::Governance
+            && self.governance_proposal.clone().unwrap().proposal_status
+                == ProposalStatus::WaitingFullVote
+        {
+            return self.cast_transition_vote(
+                caller,
+                current_time,
+                reputation_balance,
+                vote_direction,
+            );
         }
-        if self.proposal_type == ProposalType::Grant {
-            if current_time > self.proposal.clone().unwrap().vote_configuration.timeout {
-                return Err(VotingEngineError::VotingEnded);
+        self.cast_ballot(
+            caller,
+            current_time,
+            reputation_balance,
+            reputation_to_stake,
+            committed_reputation,
+            vote_direction,
+        )?;
+        self.apply_late_swing(current_time);
+        Ok(reputation_to_stake)
+    }
+
+    // Secret ballots: commits the hash of (voter, vote index, direction, stake, salt) together
+    // with the reputation locked for it, the stake revealed later can't exceed the locked
+    // reputation
+    pub fn commit_vote(
+        &mut self,
+        caller: AccountHash,
+        current_time: u64,
+        reputation_balance: U256,
+        reputation_to_commit: U256,
+        committed_reputation: U256,
+        commitment: [u8; 32],
+    ) -> Result<U256, VotingEngineError> {
+        self.check_full_vote_ongoing(caller, current_time)?;
+        if self.secret_ballot().is_none() {
+            return Err(VotingEngineError::VoteIsNotSecret);
+        }
+        if reputation_to_commit > reputation_balance.saturating_sub(committed_reputation) {
+            return Err(VotingEngineError::InvalidReputationToStake);
+        }
+        self.commitments.insert(
+            caller,
+            Commitment {
+                commitment,
+                reputation_committed: reputation_to_commit,
+                revealed: false,
+                settled: false,
+            },
+        );
+        Ok(reputation_to_commit)
+    }
+
+    // Reveals a committed ballot, returns the committed reputation that wasn't staked
+    #[allow(clippy::too_many_arguments)]
+    pub fn reveal_vote(
+        &mut self,
+        caller: AccountHash,
+        vote_index: U256,
+        current_time: u64,
+        reputation_balance: U256,
+        vote_direction: VoteDirection,
+        reputation_to_stake: U256,
+        salt: [u8; 32],
+    ) -> Result<U256, VotingEngineError> {
+        if self.secret_ballot().is_none() {
+            return Err(VotingEngineError::VoteIsNotSecret);
+        }
+        if current_time <= self.end_at() || current_time > self.reveal_end_at() {
+            return Err(VotingEngineError::RevealNotOngoing);
+        }
+        let commitment: Commitment = match self.commitments.get(&caller) {
+            Some(commitment) => commitment.clone(),
+            None => return Err(VotingEngineError::NoCommitment),
+        };
+        if commitment.revealed {
+            return Err(VotingEngineError::AlreadyRevealed);
+        }
+        if commitment_hash(
+            caller,
+            vote_index,
+            vote_direction,
+            reputation_to_stake,
+            salt,
+        ) != commitment.commitment
+        {
+            return Err(VotingEngineError::InvalidReveal);
+        }
+        if reputation_to_stake > commitment.reputation_committed {
+            return Err(VotingEngineError::InvalidReputationToStake);
+        }
+        self.record_vote(
+            caller,
+            reputation_balance,
+            reputation_to_stake,
+            vote_direction,
+            None,
+        )?;
+        self.commitments.get_mut(&caller).unwrap().revealed = true;
+        Ok(commitment.reputation_committed - reputation_to_stake)
+    }
+
+    // Once the reveal window closes, unrevealed commitments are either forfeited to the
+    // winning side or refunded. Returns the (refunded, forfeited) reputation per voter, the
+    // locks of both end with the vote
+    pub fn settle_unrevealed_commitments(
+        &mut self,
+        current_time: u64,
+    ) -> Result<(VoterReputation, VoterReputation), VotingEngineError> {
+        let mut refunds: Vec<(AccountHash, U256)> = Vec::new();
+        let mut forfeits: Vec<(AccountHash, U256)> = Vec::new();
+        let secret_ballot: SecretBallotConfiguration = match self.secret_ballot() {
+            Some(secret_ballot) => secret_ballot,
+            None => return Ok((refunds, forfeits)),
+        };
+        // Ballots can still be revealed at the end of the reveal window
+        if current_time <= self.reveal_end_at() {
+            return Err(VotingEngineError::VotingOngoing);
+        }
+        for (voter, commitment) in self.commitments.iter_mut() {
+            if commitment.revealed || commitment.settled {
+                continue;
             }
-            if self.proposal.clone().unwrap().proposal_status != ProposalStatus::InFullVote {
-                return Err(VotingEngineError::VotingNotOngoing);
+            commitment.settled = true;
+            match secret_ballot.unrevealed_policy {
+                UnrevealedPolicy::Forfeit => {
+                    self.forfeited_reputation += commitment.reputation_committed;
+                    forfeits.push((*voter, commitment.reputation_committed));
+                }
+                UnrevealedPolicy::Refund => refunds.push((*voter, commitment.reputation_committed)),
             }
-        } else {
-            if current_time
-                > self
-                    .governance_proposal
-                    .clone()
-                    .unwrap()
-                    .vote_configuration
-                    .timeout
+        }
+        Ok((refunds, forfeits))
+    }
+
+    // Liquid democracy: the delegate's ballot is cast again for each delegator, staking what the
+    // delegate staked capped by the delegator's uncommitted reputation. Delegators who already
+    // voted are skipped. Secret ballots can't be delegated.
+    // `delegators` holds (delegator, reputation balance, committed reputation), the reputation
+    // staked for each delegator is returned
+    pub fn cast_delegated_votes(
+        &mut self,
+        delegate: AccountHash,
+        current_time: u64,
+        delegators: Vec<(AccountHash, U256, U256)>,
+        reputation_to_stake: U256,
+        vote_direction: VoteDirection,
+    ) -> Result<Vec<(AccountHash, U256)>, VotingEngineError> {
+        let mut delegated_stakes: Vec<(AccountHash, U256)> = Vec::new();
+        if self.secret_ballot().is_some() {
+            return Ok(delegated_stakes);
+        }
+        for (delegator, reputation_balance, committed_reputation) in delegators {
+            if delegator == delegate
+                || committed_reputation >= reputation_balance
+                || self
+                    .check_full_vote_ongoing(delegator, current_time)
+                    .is_err()
             {
-                return Err(VotingEngineError::VotingEnded);
+                continue;
             }
-            if self.proposal.clone().unwrap().proposal_status != ProposalStatus::InFullVote {
-                return Err(VotingEngineError::VotingNotOngoing);
+            let uncommitted_reputation: U256 =
+                reputation_balance.saturating_sub(committed_reputation);
+            let delegated_stake: U256 = if reputation_to_stake > uncommitted_reputation {
+                uncommitted_reputation
+            } else {
+                reputation_to_stake
+            };
+            if delegated_stake == U256::from(0) {
+                continue;
+            }
+            if self
+                .record_vote(
+                    delegator,
+                    reputation_balance,
+                    delegated_stake,
+                    vote_direction,
+                    Some(delegate),
+                )
+                .is_ok()
+            {
+                delegated_stakes.push((delegator, delegated_stake));
             }
         }
+        if !delegated_stakes.is_empty() {
+            self.apply_late_swing(current_time);
+        }
+        Ok(delegated_stakes)
+    }
+
+    // Delegators override their delegate by voting directly before the vote ends, this drops the
+    // ballot cast on their behalf and returns the reputation it staked
+    pub fn revoke_delegated_vote(
+        &mut self,
+        caller: AccountHash,
+        current_time: u64,
+    ) -> Result<U256, VotingEngineError> {
+        let voting_data: VotingData = match self
+            .for_voters
+            .get(&caller)
+            .or_else(|| self.against_voters.get(&caller))
+            .or_else(|| self.abstain_voters.get(&caller))
+        {
+            Some(voting_data) if voting_data.delegate.is_some() => voting_data.clone(),
+            _ => return Ok(U256::from(0)),
+        };
+        self.check_ballot_revisable(current_time)?;
+        self.remove_vote(&caller);
+        Ok(voting_data.reputation_staked)
+    }
+
+    // Takes the caller's ballot out of the vote together with the ballots it was cast again for
+    // the caller's delegators, returns the reputation to release for the caller and for each
+    // delegator
+    pub fn withdraw_vote(
+        &mut self,
+        caller: AccountHash,
+        current_time: u64,
+    ) -> Result<(U256, VoterReputation), VotingEngineError> {
+        let released_reputation: U256 = self.withdraw_ballot(caller, current_time)?;
+        let released_delegated_reputation: VoterReputation = self.remove_delegated_ballots(&caller);
+        self.apply_late_swing(current_time);
+        Ok((released_reputation, released_delegated_reputation))
+    }
+
+    // Replaces the caller's ballot, returns the (released, staked) reputation and the reputation
+    // to release for each delegator, whose ballots are dropped so that they can be cast again
+    // with the new one. A ballot cast by a delegate becomes the caller's own
+    pub fn change_vote(
+        &mut self,
+        caller: AccountHash,
+        current_time: u64,
+        reputation_balance: U256,
+        reputation_to_stake: U256,
+        committed_reputation: U256,
+        vote_direction: VoteDirection,
+    ) -> Result<(U256, U256, VoterReputation), VotingEngineError> {
+        let mut voting: Voting = self.clone();
+        let released_reputation: U256 = voting.withdraw_ballot(caller, current_time)?;
+        let released_delegated_reputation: VoterReputation =
+            voting.remove_delegated_ballots(&caller);
+        voting.cast_ballot(
+            caller,
+            current_time,
+            reputation_balance,
+            reputation_to_stake,
+            committed_reputation.saturating_sub(released_reputation),
+            vote_direction,
+        )?;
+        voting.apply_late_swing(current_time);
+        *self = voting;
+        Ok((
+            released_reputation,
+            reputation_to_stake,
+            released_delegated_reputation,
+        ))
+    }
 
-        if reputation_to_stake > reputation_balance - committed_reputation {
+    fn cast_ballot(
+        &mut self,
+        caller: AccountHash,
+        current_time: u64,
+        reputation_balance: U256,
+        reputation_to_stake: U256,
+        committed_reputation: U256,
+        vote_direction: VoteDirection,
+    ) -> Result<(), VotingEngineError> {
+        self.check_full_vote_ongoing(caller, current_time)?;
+        if self.secret_ballot().is_some() {
+            return Err(VotingEngineError::VoteIsSecret);
+        }
+        if reputation_to_stake > reputation_balance.saturating_sub(committed_reputation) {
             return Err(VotingEngineError::InvalidReputationToStake);
         }
-        if self.proposal_type == ProposalType::Grant {
-            let voter_staking_limit: U256 = self
-                .proposal
-                .clone()
+        self.record_vote(
+            caller,
+            reputation_balance,
+            reputation_to_stake,
+            vote_direction,
+            None,
+        )
+    }
+
+    fn withdraw_ballot(
+        &mut self,
+        caller: AccountHash,
+        current_time: u64,
+    ) -> Result<U256, VotingEngineError> {
+        self.check_ballot_revisable(current_time)?;
+        match self.remove_vote(&caller) {
+            Some(voting_data) => Ok(voting_data.reputation_staked),
+            None => Err(VotingEngineError::NotVoted),
+        }
+    }
+
+    // Extends the vote when a ballot in the late swing window changes the leading side
+    fn apply_late_swing(&mut self, current_time: u64) {
+        let leading_side: Option<bool> = self.current_leading_side();
+        if leading_side.is_some()
+            && leading_side != self.leading_side
+            && self.late_swing_rule.window > 0
+            && current_time + self.late_swing_rule.window >= self.end_timestamp
+            && self.extensions < self.late_swing_rule.max_extensions
+        {
+            self.end_timestamp += self.late_swing_rule.extension;
+            self.extensions += 1;
+        }
+        self.leading_side = leading_side;
+    }
+
+    // Side ahead under the vote's counting rule, None on a tie
+    fn current_leading_side(&self) -> Option<bool> {
+        let counting_rule: &dyn VoteCountingRule = if self.proposal_type == ProposalType::Grant {
+            self.proposal
+                .as_ref()
                 .unwrap()
                 .vote_configuration
-                .voter_staking_limits;
-            let staking_percentage: U256 = reputation_balance / reputation_balance;
-            if staking_percentage > voter_staking_limit {
-                return Err(VotingEngineError::StakingLimitReached);
+                .counting_rule
+                .rule()
+        } else {
+            self.governance_proposal
+                .as_ref()
+                .unwrap()
+                .vote_configuration
+                .counting_rule
+                .rule()
+        };
+        let (for_weight, against_weight) =
+            counting_rule.tally(&mut self.for_voters.values().chain(self.against_voters.values()));
+        if for_weight > against_weight {
+            Some(true)
+        } else if against_weight > for_weight {
+            Some(false)
+        } else {
+            None
+        }
+    }
+
+    fn remove_delegated_ballots(&mut self, delegate: &AccountHash) -> VoterReputation {
+        let delegators: Vec<AccountHash> = self
+            .for_voters
+            .iter()
+            .chain(self.against_voters.iter())
+            .chain(self.abstain_voters.iter())
+            .filter(|(_, voting_data)| voting_data.delegate == Some(*delegate))
+            .map(|(delegator, _)| *delegator)
+            .collect();
+        delegators
+            .into_iter()
+            .filter_map(|delegator| {
+                self.remove_vote(&delegator)
+                    .map(|voting_data| (delegator, voting_data.reputation_staked))
+            })
+            .collect()
+    }
+
+    fn remove_vote(&mut self, caller: &AccountHash) -> Option<VotingData> {
+        let voting_data: VotingData = match self.for_voters.remove(caller) {
+            Some(voting_data) => {
+                self.for_votes -= voting_data.reputation_staked;
+                voting_data
+            }
+            None => match self.against_voters.remove(caller) {
+                Some(voting_data) => {
+                    self.against_votes -= voting_data.reputation_staked;
+                    voting_data
+                }
+                None => {
+                    let voting_data: VotingData = self.abstain_voters.remove(caller)?;
+                    self.abstain_votes -= voting_data.reputation_staked;
+                    voting_data
+                }
+            },
+        };
+        self.total_staked_reputation -= voting_data.reputation_staked;
+        self.total_members -= 1;
+        Some(voting_data)
+    }
+
+    fn check_full_vote_ongoing(
+        &self,
+        caller: AccountHash,
+        current_time: u64,
+    ) -> Result<(), VotingEngineError> {
+        if self.has_voted(&caller) || self.commitments.contains_key(&caller) {
+            return Err(VotingEngineError::AlreadyVoted);
+        }
+        self.check_full_vote_open(current_time)
+    }
+
+    // Taking a ballot back could undo a locked outcome
+    fn check_ballot_revisable(&self, current_time: u64) -> Result<(), VotingEngineError> {
+        self.check_full_vote_open(current_time)?;
+        if self.is_outcome_locked() {
+            return Err(VotingEngineError::OutcomeLocked);
+        }
+        Ok(())
+    }
+
+    fn check_full_vote_open(&self, current_time: u64) -> Result<(), VotingEngineError> {
+        if current_time > self.end_at() {
+            return Err(VotingEngineError::VotingEnded);
+        }
+        if self.proposal_type == ProposalType::Grant {
+            if self.proposal.clone().unwrap().proposal_status != ProposalStatus::InFullVote {
+                return Err(VotingEngineError::VotingNotOngoing);
+            }
+        } else {
+            if self.governance_proposal.clone().unwrap().proposal_status
+                != ProposalStatus::InFullVote
+            {
+                return Err(VotingEngineError::VotingNotOngoing);
             }
         }
+        Ok(())
+    }
 
+    fn record_vote(
+        &mut self,
+        caller: AccountHash,
+        reputation_balance: U256,
+        reputation_to_stake: U256,
+        vote_direction: VoteDirection,
+        delegate: Option<AccountHash>,
+    ) -> Result<(), VotingEngineError> {
+        // Members who held no reputation at the snapshot do not take part in the vote
+        if reputation_balance == U256::from(0) {
+            return Err(VotingEngineError::NoReputationAtSnapshot);
+        }
+        self.check_staking_limits(reputation_balance, reputation_to_stake)?;
         let voting_data: VotingData = VotingData {
             claimed: false,
             reputation_staked: reputation_to_stake,
             vote: vote_direction,
+            delegate,
         };
-        if vote_direction {
-            self.for_voters.insert(caller, voting_data);
-            self.for_votes += reputation_to_stake;
-        } else {
-            self.against_voters.insert(caller, voting_data);
-            self.against_votes += reputation_to_stake;
-        }
-        if !self.for_voters.contains_key(&caller) && !self.against_voters.contains_key(&caller) {
+        if !self.has_voted(&caller) {
             // First time voting on this proposal
             self.total_members += 1;
         }
+        match vote_direction {
+            VoteDirection::For => {
+                self.for_voters.insert(caller, voting_data);
+                self.for_votes += reputation_to_stake;
+            }
+            VoteDirection::Against => {
+                self.against_voters.insert(caller, voting_data);
+                self.against_votes += reputation_to_stake;
+            }
+            VoteDirection::Abstain => {
+                self.abstain_voters.insert(caller, voting_data);
+                self.abstain_votes += reputation_to_stake;
+            }
+        }
         self.total_staked_reputation += reputation_to_stake;
         Ok(())
     }
 
+    // Quorum percentages are rounded up to whole members and reputation units
+    fn member_quorum(&self, percentage: u64) -> u64 {
+        percentage_of_rounded_up(U256::from(self.eligible_members), percentage).as_u64()
+    }
+
+    fn reputation_quorum(&self, percentage: u64) -> U256 {
+        percentage_of_rounded_up(self.eligible_reputation, percentage)
+    }
+
+    // Limits are in basis points, the total stake share is measured against the reputation
+    // quorum until the vote's stake exceeds it, so early voters cannot dominate either. Without
+    // a reputation quorum the first stake sets the scale
+    fn check_staking_limits(
+        &self,
+        reputation_balance: U256,
+        reputation_to_stake: U256,
+    ) -> Result<(), VotingEngineError> {
+        let (staking_limits, reputation_quorum): (StakingLimits, u64) =
+            if self.proposal_type == ProposalType::Governance {
+                let vote_configuration = &self
+                    .governance_proposal
+                    .as_ref()
+                    .unwrap()
+                    .vote_configuration;
+                (
+                    vote_configuration.voter_staking_limits,
+                    vote_configuration.full_vote_quorum,
+                )
+            } else {
+                let vote_configuration = &self.proposal.as_ref().unwrap().vote_configuration;
+                (
+                    vote_configuration.voter_staking_limits,
+                    vote_configuration.reputation_quorum,
+                )
+            };
+        if reputation_to_stake * U256::from(10000)
+            > reputation_balance * U256::from(staking_limits.balance_share)
+        {
+            return Err(VotingEngineError::StakingLimitReached);
+        }
+        let reputation_quorum: U256 = self.reputation_quorum(reputation_quorum);
+        if let Some(total_stake_share) = staking_limits.total_stake_share {
+            if self.total_staked_reputation == U256::from(0) && reputation_quorum == U256::from(0) {
+                return Ok(());
+            }
+            let total_stake: U256 = core::cmp::max(
+                self.total_staked_reputation + reputation_to_stake,
+                reputation_quorum,
+            );
+            if reputation_to_stake * U256::from(10000) > total_stake * U256::from(total_stake_share)
+            {
+                return Err(VotingEngineError::TotalStakeLimitReached);
+            }
+        }
+        Ok(())
+    }
+
+    fn has_voted(&self, caller: &AccountHash) -> bool {
+        self.for_voters.contains_key(caller)
+            || self.against_voters.contains_key(caller)
+            || self.abstain_voters.contains_key(caller)
+    }
+
+    // Transition votes are informal: they are weighted by the voter's reputation
+    // balance and nothing gets staked
+    fn cast_transition_vote(
+        &mut self,
+        caller: AccountHash,
+        current_time: u64,
+        reputation_balance: U256,
+        vote_direction: VoteDirection,
+    ) -> Result<U256, VotingEngineError> {
+        if self.transition_voters.contains_key(&caller) {
+            return Err(VotingEngineError::AlreadyVoted);
+        }
+        if current_time > self.end_at() {
+            return Err(VotingEngineError::VotingEnded);
+        }
+        let voting_data: VotingData = VotingData {
+            claimed: false,
+            reputation_staked: reputation_balance,
+            vote: vote_direction,
+            delegate: None,
+        };
+        match vote_direction {
+            VoteDirection::For => self.transition_for_votes += reputation_balance,
+            VoteDirection::Against => self.transition_against_votes += reputation_balance,
+            // Counted toward the quorum from the transition voters
+            VoteDirection::Abstain => (),
+        }
+        self.transition_voters.insert(caller, voting_data);
+        Ok(U256::from(0))
+    }
+
     pub fn serialize(&self) -> VotingSerialized {
         let mut proposal_option: Option<ProposalSerialized>;
         let mut governance_proposal_option: Option<GovernanceProposalSerialized>;
@@ -380,40 +1358,93 @@ impl Voting {
                     governance_proposal_option,
                     self.proposal_type as u8,
                 ),
+                (
+                    self.end_timestamp,
+                    self.late_swing_rule.serialize(),
+                    (self.extensions, self.leading_side),
+                ),
             ),
-            (self.for_votes, self.against_votes, self.input_reputation),
             (
-                self.serialize_voters().0,
-                self.serialize_voters().1,
-                self.result as u8,
+                (
+                    self.for_votes,
+                    self.against_votes,
+                    (
+                        self.input_reputation,
+                        (self.distributed_rewards, self.uncollected_stakes),
+                        self.dust_collected,
+                    ),
+                ),
+                self.serialize_transition_vote(),
+                self.serialize_secret_ballot(),
+            ),
+            (
+                (
+                    Voting::serialize_voters(&self.for_voters),
+                    Voting::serialize_voters(&self.against_voters),
+                    Voting::serialize_voters(&self.abstain_voters),
+                ),
+                (
+                    self.abstain_votes,
+                    self.proposer_stake_reclaimed,
+                    self.outcome_report
+                        .as_ref()
+                        .map(|report| report.serialize()),
+                ),
+                (
+                    self.result as u8,
+                    (self.snapshot_timestamp, self.eligible_members),
+                    (
+                        self.eligible_reputation,
+                        self.governance_execution
+                            .map(|governance_execution| governance_execution.serialize()),
+                    ),
+                ),
             ),
         )
     }
 
-    fn serialize_voters(&self) -> (VotersSerialized, VotersSerialized) {
-        let mut for_voters_output = BTreeMap::new();
-        for (key, voting_data) in self.for_voters.iter() {
-            for_voters_output.insert(
+    fn serialize_voters(voters: &BTreeMap<AccountHash, VotingData>) -> VotersSerialized {
+        let mut voters_output: VotersSerialized = BTreeMap::new();
+        for (key, voting_data) in voters.iter() {
+            voters_output.insert(
                 key.value(),
                 (
                     voting_data.reputation_staked,
-                    voting_data.vote,
-                    voting_data.claimed,
+                    voting_data.vote as u8,
+                    (
+                        voting_data.claimed,
+                        voting_data.delegate.map(|delegate| delegate.value()),
+                    ),
                 ),
             );
         }
-        let mut against_voters_output = BTreeMap::new();
-        for (key, voting_data) in self.against_voters.iter() {
-            against_voters_output.insert(
+        voters_output
+    }
+
+    fn serialize_transition_vote(&self) -> TransitionVoteSerialized {
+        (
+            (
+                self.transition_for_votes,
+                self.transition_against_votes,
+                self.full_vote_start_timestamp,
+            ),
+            Voting::serialize_voters(&self.transition_voters),
+        )
+    }
+
+    fn serialize_secret_ballot(&self) -> SecretBallotSerialized {
+        let mut commitments_output: CommitmentsSerialized = BTreeMap::new();
+        for (key, commitment) in self.commitments.iter() {
+            commitments_output.insert(
                 key.value(),
                 (
-                    voting_data.reputation_staked,
-                    voting_data.vote,
-                    voting_data.claimed,
+                    commitment.commitment,
+                    commitment.reputation_committed,
+                    (commitment.revealed, commitment.settled),
                 ),
             );
         }
-        (for_voters_output, against_voters_output)
+        (commitments_output, self.forfeited_reputation)
     }
 
     pub fn deserialize(serialized_voting: VotingSerialized) -> Voting {
@@ -438,12 +1469,42 @@ impl Voting {
             proposal: proposal,
             governance_proposal: governance_proposal,
             proposal_type: proposal_type,
-            for_votes: serialized_voting.1 .0,
-            against_votes: serialized_voting.1 .1,
-            input_reputation: serialized_voting.1 .2,
-            for_voters: Voting::deserialize_voters(serialized_voting.2 .0),
-            against_voters: Voting::deserialize_voters(serialized_voting.2 .1),
-            result: serialized_voting.2 .2.into(),
+            for_votes: serialized_voting.1 .0 .0,
+            against_votes: serialized_voting.1 .0 .1,
+            input_reputation: serialized_voting.1 .0 .2 .0,
+            distributed_rewards: serialized_voting.1 .0 .2 .1 .0,
+            uncollected_stakes: serialized_voting.1 .0 .2 .1 .1,
+            dust_collected: serialized_voting.1 .0 .2 .2,
+            for_voters: Voting::deserialize_voters(serialized_voting.2 .0 .0),
+            against_voters: Voting::deserialize_voters(serialized_voting.2 .0 .1),
+            abstain_voters: Voting::deserialize_voters(serialized_voting.2 .0 .2),
+            abstain_votes: serialized_voting.2 .1 .0,
+            proposer_stake_reclaimed: serialized_voting.2 .1 .1,
+            outcome_report: serialized_voting
+                .2
+                 .1
+                 .2
+                .map(VoteOutcomeReport::deserialize),
+            result: serialized_voting.2 .2 .0.into(),
+            snapshot_timestamp: serialized_voting.2 .2 .1 .0,
+            eligible_members: serialized_voting.2 .2 .1 .1,
+            eligible_reputation: serialized_voting.2 .2 .2 .0,
+            governance_execution: serialized_voting
+                .2
+                 .2
+                 .2
+                 .1
+                .map(GovernanceExecution::deserialize),
+            transition_for_votes: serialized_voting.1 .1 .0 .0,
+            transition_against_votes: serialized_voting.1 .1 .0 .1,
+            full_vote_start_timestamp: serialized_voting.1 .1 .0 .2,
+            transition_voters: Voting::deserialize_voters(serialized_voting.1 .1 .1),
+            commitments: Voting::deserialize_commitments(serialized_voting.1 .2 .0),
+            forfeited_reputation: serialized_voting.1 .2 .1,
+            end_timestamp: serialized_voting.0 .2 .0,
+            late_swing_rule: LateSwingRule::deserialize(serialized_voting.0 .2 .1),
+            extensions: serialized_voting.0 .2 .2 .0,
+            leading_side: serialized_voting.0 .2 .2 .1,
         }
     }
 
@@ -456,14 +1517,33 @@ impl Voting {
                 AccountHash::new(key),
                 VotingData {
                     reputation_staked: voting_data.0,
-                    vote: voting_data.1,
-                    claimed: voting_data.2,
+                    vote: voting_data.1.into(),
+                    claimed: voting_data.2 .0,
+                    delegate: voting_data.2 .1.map(AccountHash::new),
                 },
             );
         }
         deserialized_voters
     }
 
+    fn deserialize_commitments(
+        commitments_serialized: CommitmentsSerialized,
+    ) -> BTreeMap<AccountHash, Commitment> {
+        let mut deserialized_commitments: BTreeMap<AccountHash, Commitment> = BTreeMap::new();
+        for (key, commitment) in commitments_serialized {
+            deserialized_commitments.insert(
+                AccountHash::new(key),
+                Commitment {
+                    commitment: commitment.0,
+                    reputation_committed: commitment.1,
+                    revealed: commitment.2 .0,
+                    settled: commitment.2 .1,
+                },
+            );
+        }
+        deserialized_commitments
+    }
+
     // #[cfg(test)]
     // mod tests {
     //     use super::*;
@@ -813,6 +1893,40 @@ impl Voting {
     //     }
 }
 
+// Hash a secret ballot commits to: blake2b of the serialized (voter, vote index, (direction,
+// stake, salt)), binding it to its voter and vote so it can't be replayed by someone else
+pub fn commitment_hash(
+    voter: AccountHash,
+    vote_index: U256,
+    vote_direction: VoteDirection,
+    reputation_to_stake: U256,
+    salt: [u8; 32],
+) -> [u8; 32] {
+    let bytes: Vec<u8> = (
+        voter,
+        vote_index,
+        (vote_direction as u8, reputation_to_stake, salt),
+    )
+        .to_bytes()
+        .unwrap_or_default();
+    let mut output: [u8; 32] = [0u8; 32];
+    let mut hasher = VarBlake2b::new(32).unwrap();
+    hasher.update(bytes);
+    hasher.finalize_variable(|hash| output.copy_from_slice(hash));
+    output
+}
+
+impl From<u8> for VoteDirection {
+    fn from(orig: u8) -> Self {
+        match orig {
+            0 => VoteDirection::For,
+            1 => VoteDirection::Against,
+            2 => VoteDirection::Abstain,
+            _ => VoteDirection::Abstain,
+        }
+    }
+}
+
 impl From<u8> for VoteResult {
     fn from(orig: u8) -> Self {
         match orig {
@@ -828,3 +1942,1149 @@ impl From<u8> for VoteResult {
         };
     }
 }
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use crate::governance_execution::ExecutionStatus;
+    use crate::proposal::{Ratios, StakingLimits, VoteConfiguration};
+    use crate::vote_policy::VotePolicy;
+    use alloc::string::ToString;
+
+    const ALI: AccountHash = AccountHash::new([1u8; 32]);
+    const BOB: AccountHash = AccountHash::new([2u8; 32]);
+    const JON: AccountHash = AccountHash::new([3u8; 32]);
+    const END: u64 = 100;
+    const SALT: [u8; 32] = [7u8; 32];
+
+    // Grant vote ending at END, three members holding 100 reputation each at the snapshot
+    pub(crate) fn grant_vote(
+        counting_rule: CountingRule,
+        secret_ballot: Option<SecretBallotConfiguration>,
+    ) -> Voting {
+        let proposal: Proposal = Proposal {
+            name: "grant".to_string(),
+            storage_pointer: "pointer".to_string(),
+            storage_fingerprint: "fingerprint".to_string(),
+            proposal_type: ProposalType::Grant,
+            proposer: ALI,
+            staked_rep: U256::from(0),
+            citations: Vec::new(),
+            ratios: Ratios {
+                policing_ratio: 50,
+                op_ratio: 50,
+                citation_ratio: 0,
+            },
+            vote_configuration: VoteConfiguration {
+                member_quorum: 0,
+                reputation_quorum: 0,
+                threshold: 50,
+                timeout: END,
+                voter_staking_limits: StakingLimits {
+                    balance_share: 10000,
+                    total_stake_share: None,
+                },
+                secret_ballot,
+                counting_rule,
+            },
+            milestones: BTreeMap::new(),
+            proposal_status: ProposalStatus::InFullVote,
+            sponsors: BTreeMap::new(),
+            cost: U256::from(0),
+        };
+        let late_swing_rule: LateSwingRule = LateSwingRule {
+            window: 0,
+            extension: 0,
+            max_extensions: 0,
+        };
+        Voting::new(0, proposal.serialize(), late_swing_rule, 3, U256::from(300)).unwrap()
+    }
+
+    fn secret_vote(unrevealed_policy: UnrevealedPolicy) -> Voting {
+        grant_vote(
+            CountingRule::SimpleMajority,
+            Some(SecretBallotConfiguration {
+                reveal_timeout: 50,
+                unrevealed_policy,
+            }),
+        )
+    }
+
+    // Locks `locked` reputation behind a ballot staking `stake`
+    fn commit(
+        voting: &mut Voting,
+        voter: AccountHash,
+        direction: VoteDirection,
+        stake: u64,
+        locked: u64,
+    ) {
+        let commitment: [u8; 32] =
+            commitment_hash(voter, U256::from(0), direction, U256::from(stake), SALT);
+        voting
+            .commit_vote(
+                voter,
+                10,
+                U256::from(100),
+                U256::from(locked),
+                U256::from(0),
+                commitment,
+            )
+            .unwrap();
+    }
+
+    fn reveal(
+        voting: &mut Voting,
+        voter: AccountHash,
+        current_time: u64,
+        direction: VoteDirection,
+        stake: u64,
+    ) -> Result<U256, VotingEngineError> {
+        voting.reveal_vote(
+            voter,
+            U256::from(0),
+            current_time,
+            U256::from(100),
+            direction,
+            U256::from(stake),
+            SALT,
+        )
+    }
+
+    #[test]
+    fn test_secret_ballot_is_counted_once_revealed() {
+        let mut voting = secret_vote(UnrevealedPolicy::Refund);
+        commit(&mut voting, BOB, VoteDirection::For, 30, 40);
+        assert_eq!(
+            voting.cast_vote(
+                JON,
+                10,
+                U256::from(100),
+                U256::from(10),
+                U256::from(0),
+                VoteDirection::For
+            ),
+            Err(VotingEngineError::VoteIsSecret)
+        );
+        assert_eq!(
+            reveal(&mut voting, BOB, 10, VoteDirection::For, 30),
+            Err(VotingEngineError::RevealNotOngoing)
+        );
+        assert_eq!(voting.for_votes, U256::from(0));
+
+        // The locked reputation that wasn't staked is released
+        assert_eq!(
+            reveal(&mut voting, BOB, END + 1, VoteDirection::For, 30),
+            Ok(U256::from(10))
+        );
+        assert_eq!(voting.for_votes, U256::from(30));
+        assert_eq!(
+            reveal(&mut voting, BOB, END + 1, VoteDirection::For, 30),
+            Err(VotingEngineError::AlreadyRevealed)
+        );
+        assert_eq!(
+            reveal(&mut voting, BOB, END + 51, VoteDirection::For, 30),
+            Err(VotingEngineError::RevealNotOngoing)
+        );
+    }
+
+    #[test]
+    fn test_reveal_must_match_commitment() {
+        let mut voting = secret_vote(UnrevealedPolicy::Refund);
+        commit(&mut voting, BOB, VoteDirection::For, 30, 30);
+        assert_eq!(
+            reveal(&mut voting, BOB, END + 1, VoteDirection::Against, 30),
+            Err(VotingEngineError::InvalidReveal)
+        );
+        assert_eq!(
+            reveal(&mut voting, BOB, END + 1, VoteDirection::For, 31),
+            Err(VotingEngineError::InvalidReveal)
+        );
+        assert_eq!(
+            voting.reveal_vote(
+                BOB,
+                U256::from(0),
+                END + 1,
+                U256::from(100),
+                VoteDirection::For,
+                U256::from(30),
+                [8u8; 32],
+            ),
+            Err(VotingEngineError::InvalidReveal)
+        );
+    }
+
+    #[test]
+    fn test_commitment_is_bound_to_voter_and_vote() {
+        let mut voting = secret_vote(UnrevealedPolicy::Refund);
+        // BOB copies the commitment JON published
+        let copied_commitment: [u8; 32] =
+            commitment_hash(JON, U256::from(0), VoteDirection::For, U256::from(30), SALT);
+        voting
+            .commit_vote(
+                BOB,
+                10,
+                U256::from(100),
+                U256::from(30),
+                U256::from(0),
+                copied_commitment,
+            )
+            .unwrap();
+        assert_eq!(
+            reveal(&mut voting, BOB, END + 1, VoteDirection::For, 30),
+            Err(VotingEngineError::InvalidReveal)
+        );
+        assert_ne!(
+            commitment_hash(JON, U256::from(1), VoteDirection::For, U256::from(30), SALT),
+            copied_commitment
+        );
+    }
+
+    #[test]
+    fn test_delegated_votes_are_capped_and_revocable() {
+        let mut voting = grant_vote(CountingRule::SimpleMajority, None);
+        voting
+            .cast_vote(
+                JON,
+                10,
+                U256::from(100),
+                U256::from(20),
+                U256::from(0),
+                VoteDirection::Against,
+            )
+            .unwrap();
+        // ALI has 30 uncommitted reputation, JON already voted directly
+        let delegated_stakes: Vec<(AccountHash, U256)> = voting
+            .cast_delegated_votes(
+                BOB,
+                10,
+                alloc::vec![
+                    (ALI, U256::from(100), U256::from(70)),
+                    (JON, U256::from(100), U256::from(0)),
+                ],
+                U256::from(50),
+                VoteDirection::For,
+            )
+            .unwrap();
+        assert_eq!(delegated_stakes, alloc::vec![(ALI, U256::from(30))]);
+        assert_eq!(voting.for_votes, U256::from(30));
+        assert_eq!(voting.for_voters.get(&ALI).unwrap().delegate, Some(BOB));
+        assert_eq!(voting.against_votes, U256::from(20));
+
+        // ALI overrides the delegate by voting directly
+        assert_eq!(voting.revoke_delegated_vote(ALI, 10), Ok(U256::from(30)));
+        assert_eq!(voting.for_votes, U256::from(0));
+        assert_eq!(voting.total_members, 1);
+        // Direct ballots are not revoked
+        assert_eq!(voting.revoke_delegated_vote(JON, 10), Ok(U256::from(0)));
+    }
+
+    #[test]
+    fn test_delegated_ballots_follow_the_delegate() {
+        let delegated_vote = || {
+            let mut voting = grant_vote(CountingRule::SimpleMajority, None);
+            cast(&mut voting, BOB, VoteDirection::For, 40);
+            voting
+                .cast_delegated_votes(
+                    BOB,
+                    10,
+                    alloc::vec![(ALI, U256::from(100), U256::from(0))],
+                    U256::from(40),
+                    VoteDirection::For,
+                )
+                .unwrap();
+            voting
+        };
+        let mut voting = delegated_vote();
+        assert_eq!(
+            voting.withdraw_vote(BOB, 20),
+            Ok((U256::from(40), alloc::vec![(ALI, U256::from(40))]))
+        );
+        assert_eq!(voting.for_votes, U256::from(0));
+        assert_eq!(voting.total_members, 0);
+        assert_eq!(voting.total_staked_reputation, U256::from(0));
+
+        // The delegated ballots are dropped, to be cast again with the new ballot
+        let mut voting = delegated_vote();
+        assert_eq!(
+            voting.change_vote(
+                BOB,
+                20,
+                U256::from(100),
+                U256::from(10),
+                U256::from(40),
+                VoteDirection::Against
+            ),
+            Ok((
+                U256::from(40),
+                U256::from(10),
+                alloc::vec![(ALI, U256::from(40))]
+            ))
+        );
+        assert_eq!(voting.for_votes, U256::from(0));
+        assert_eq!(voting.against_votes, U256::from(10));
+        assert_eq!(
+            voting.cast_delegated_votes(
+                BOB,
+                20,
+                alloc::vec![(ALI, U256::from(100), U256::from(0))],
+                U256::from(10),
+                VoteDirection::Against,
+            ),
+            Ok(alloc::vec![(ALI, U256::from(10))])
+        );
+        assert_eq!(voting.against_votes, U256::from(20));
+        assert_eq!(voting.total_members, 2);
+    }
+
+    #[test]
+    fn test_secret_ballots_are_not_delegated() {
+        let mut voting = secret_vote(UnrevealedPolicy::Refund);
+        assert_eq!(
+            voting.cast_delegated_votes(
+                BOB,
+                10,
+                alloc::vec![(ALI, U256::from(100), U256::from(0))],
+                U256::from(50),
+                VoteDirection::For,
+            ),
+            Ok(Vec::new())
+        );
+    }
+
+    fn cast(voting: &mut Voting, voter: AccountHash, direction: VoteDirection, stake: u64) {
+        voting
+            .cast_vote(
+                voter,
+                10,
+                U256::from(100),
+                U256::from(stake),
+                U256::from(0),
+                direction,
+            )
+            .unwrap();
+    }
+
+    #[test]
+    fn test_change_and_withdraw_vote() {
+        let mut voting = grant_vote(CountingRule::SimpleMajority, None);
+        cast(&mut voting, BOB, VoteDirection::For, 40);
+        // BOB's 40 are locked, so staking 60 only adds 20 to the lock
+        assert_eq!(
+            voting.change_vote(
+                BOB,
+                20,
+                U256::from(100),
+                U256::from(60),
+                U256::from(40),
+                VoteDirection::Against
+            ),
+            Ok((U256::from(40), U256::from(60), Vec::new()))
+        );
+        assert_eq!(voting.for_votes, U256::from(0));
+        assert_eq!(voting.against_votes, U256::from(60));
+        assert_eq!(voting.total_members, 1);
+        assert_eq!(voting.total_staked_reputation, U256::from(60));
+
+        // A rejected change leaves the ballot as it was
+        let before: Voting = voting.clone();
+        assert_eq!(
+            voting.change_vote(
+                BOB,
+                20,
+                U256::from(100),
+                U256::from(101),
+                U256::from(60),
+                VoteDirection::For
+            ),
+            Err(VotingEngineError::InvalidReputationToStake)
+        );
+        assert_eq!(voting, before);
+
+        assert_eq!(
+            voting.withdraw_vote(BOB, 30),
+            Ok((U256::from(60), Vec::new()))
+        );
+        assert_eq!(voting.against_votes, U256::from(0));
+        assert_eq!(voting.total_members, 0);
+        assert_eq!(
+            voting.withdraw_vote(BOB, 30),
+            Err(VotingEngineError::NotVoted)
+        );
+    }
+
+    #[test]
+    fn test_ballots_are_final_after_the_timeout() {
+        let mut voting = grant_vote(CountingRule::SimpleMajority, None);
+        cast(&mut voting, BOB, VoteDirection::For, 40);
+        assert_eq!(
+            voting.withdraw_vote(BOB, END + 1),
+            Err(VotingEngineError::VotingEnded)
+        );
+        assert_eq!(
+            voting.change_vote(
+                BOB,
+                END + 1,
+                U256::from(100),
+                U256::from(40),
+                U256::from(40),
+                VoteDirection::Against
+            ),
+            Err(VotingEngineError::VotingEnded)
+        );
+    }
+
+    #[test]
+    fn test_late_swing_extends_the_vote() {
+        let mut voting = grant_vote(CountingRule::SimpleMajority, None);
+        voting.late_swing_rule = LateSwingRule {
+            window: 10,
+            extension: 20,
+            max_extensions: 1,
+        };
+        cast(&mut voting, BOB, VoteDirection::For, 40);
+        assert_eq!(voting.end_at(), END);
+
+        // Flipping the leading side inside the window extends the vote once
+        voting
+            .cast_vote(
+                JON,
+                95,
+                U256::from(100),
+                U256::from(50),
+                U256::from(0),
+                VoteDirection::Against,
+            )
+            .unwrap();
+        assert_eq!(voting.end_at(), END + 20);
+        voting
+            .cast_vote(
+                ALI,
+                115,
+                U256::from(100),
+                U256::from(20),
+                U256::from(0),
+                VoteDirection::For,
+            )
+            .unwrap();
+        assert_eq!(voting.end_at(), END + 20);
+        assert_eq!(voting.extensions, 1);
+    }
+
+    #[test]
+    fn test_locked_outcome_finalizes_early() {
+        let mut voting = grant_vote(CountingRule::SimpleMajority, None);
+        cast(&mut voting, ALI, VoteDirection::For, 100);
+        cast(&mut voting, JON, VoteDirection::Against, 10);
+        // BOB could still swing the vote
+        assert!(!voting.is_outcome_locked());
+        assert_eq!(
+            voting.calculate_vote_outcome(END - 1, 0),
+            Err(VotingEngineError::VotingOngoing)
+        );
+        assert_eq!(
+            voting.withdraw_vote(JON, 20),
+            Ok((U256::from(10), Vec::new()))
+        );
+        cast(&mut voting, JON, VoteDirection::Against, 10);
+
+        cast(&mut voting, BOB, VoteDirection::For, 100);
+        assert!(voting.is_outcome_locked());
+        // Taking a ballot back could undo the locked outcome
+        assert_eq!(
+            voting.withdraw_vote(BOB, 20),
+            Err(VotingEngineError::OutcomeLocked)
+        );
+        assert_eq!(
+            voting.change_vote(
+                BOB,
+                20,
+                U256::from(100),
+                U256::from(100),
+                U256::from(100),
+                VoteDirection::Against
+            ),
+            Err(VotingEngineError::OutcomeLocked)
+        );
+        assert_eq!(
+            voting.calculate_vote_outcome(20, 0),
+            Ok(VoteResult::Approved)
+        );
+        assert_eq!(
+            voting.withdraw_vote(BOB, 21),
+            Err(VotingEngineError::VotingNotOngoing)
+        );
+        assert_eq!(voting.claim_reputation(ALI), Ok(U256::from(5)));
+    }
+
+    #[test]
+    fn test_abstentions_count_toward_quorum_only() {
+        let mut voting = grant_vote(CountingRule::SimpleMajority, None);
+        voting
+            .proposal
+            .as_mut()
+            .unwrap()
+            .vote_configuration
+            .member_quorum = 100;
+        cast(&mut voting, ALI, VoteDirection::For, 10);
+        cast(&mut voting, BOB, VoteDirection::Against, 5);
+        let mut without_abstention: Voting = voting.clone();
+        assert_eq!(
+            without_abstention.calculate_vote_outcome(END + 1, 0),
+            Ok(VoteResult::MemberQuorumUnmet)
+        );
+
+        cast(&mut voting, JON, VoteDirection::Abstain, 90);
+        assert_eq!(
+            voting.calculate_vote_outcome(END + 1, 0),
+            Ok(VoteResult::Approved)
+        );
+        // Abstentions keep their stake and share none of the losing one
+        assert_eq!(voting.claim_reputation(JON), Ok(U256::from(0)));
+        assert_eq!(voting.claim_reputation(ALI), Ok(U256::from(5)));
+    }
+
+    #[test]
+    fn test_stakes_are_refunded_when_the_vote_fails() {
+        let mut voting = grant_vote(CountingRule::SimpleMajority, None);
+        voting
+            .proposal
+            .as_mut()
+            .unwrap()
+            .vote_configuration
+            .reputation_quorum = 50;
+        voting.proposal.as_mut().unwrap().staked_rep = U256::from(5);
+        cast(&mut voting, BOB, VoteDirection::For, 100);
+        assert_eq!(
+            voting.get_stake(BOB),
+            Err(VotingEngineError::VoteDidNotFail)
+        );
+        assert_eq!(
+            voting.calculate_vote_outcome(END + 1, 0),
+            Ok(VoteResult::ReputationQuorumUnmet)
+        );
+        assert_eq!(voting.get_stake(BOB), Ok(U256::from(100)));
+        assert_eq!(
+            voting.get_stake(BOB),
+            Err(VotingEngineError::ReputationAlreadyClaimed)
+        );
+        assert_eq!(
+            voting.get_proposer_stake(BOB),
+            Err(VotingEngineError::NoReputationToClaim)
+        );
+        assert_eq!(voting.get_proposer_stake(ALI), Ok(U256::from(5)));
+        assert_eq!(
+            voting.claim_reputation(BOB),
+            Err(VotingEngineError::VoteFailed)
+        );
+    }
+
+    #[test]
+    fn test_unrevealed_commitments_are_settled_once() {
+        let mut voting = secret_vote(UnrevealedPolicy::Forfeit);
+        commit(&mut voting, BOB, VoteDirection::For, 30, 30);
+        commit(&mut voting, JON, VoteDirection::Against, 20, 20);
+        reveal(&mut voting, BOB, END + 1, VoteDirection::For, 30).unwrap();
+        // JON could still reveal at the end of the window
+        assert_eq!(
+            voting.settle_unrevealed_commitments(END + 50),
+            Err(VotingEngineError::VotingOngoing)
+        );
+        // Then the lock is forfeited to the winners
+        assert_eq!(
+            voting.settle_unrevealed_commitments(END + 51),
+            Ok((Vec::new(), alloc::vec![(JON, U256::from(20))]))
+        );
+        assert_eq!(
+            voting.settle_unrevealed_commitments(END + 51),
+            Ok((Vec::new(), Vec::new()))
+        );
+        assert_eq!(
+            voting.calculate_vote_outcome(END + 51, 0),
+            Ok(VoteResult::Approved)
+        );
+        assert_eq!(voting.claim_reputation(BOB), Ok(U256::from(20)));
+
+        let mut voting = secret_vote(UnrevealedPolicy::Refund);
+        commit(&mut voting, JON, VoteDirection::Against, 20, 25);
+        assert_eq!(
+            voting.settle_unrevealed_commitments(END + 51),
+            Ok((alloc::vec![(JON, U256::from(25))], Vec::new()))
+        );
+        assert_eq!(voting.forfeited_reputation, U256::from(0));
+    }
+
+    #[test]
+    fn test_forfeits_of_a_failed_vote_go_to_the_dao() {
+        let mut voting = secret_vote(UnrevealedPolicy::Forfeit);
+        voting
+            .proposal
+            .as_mut()
+            .unwrap()
+            .vote_configuration
+            .reputation_quorum = 50;
+        commit(&mut voting, BOB, VoteDirection::For, 30, 30);
+        commit(&mut voting, JON, VoteDirection::Against, 20, 25);
+        reveal(&mut voting, BOB, END + 1, VoteDirection::For, 30).unwrap();
+        voting.settle_unrevealed_commitments(END + 51).unwrap();
+        // 5 of JON's 25 could not be debited
+        voting.record_uncollected_stake(U256::from(5));
+        assert_eq!(
+            voting.calculate_vote_outcome(END + 51, 0),
+            Ok(VoteResult::ReputationQuorumUnmet)
+        );
+        // BOB's stake was only locked, everything debited leaves the voting engine
+        assert_eq!(voting.get_stake(BOB), Ok(U256::from(30)));
+        assert_eq!(voting.take_rounding_dust(), U256::from(20));
+        assert_eq!(voting.take_rounding_dust(), U256::from(0));
+    }
+
+    #[test]
+    fn test_staking_limits() {
+        let mut voting = grant_vote(CountingRule::SimpleMajority, None);
+        let vote_configuration: &mut VoteConfiguration =
+            &mut voting.proposal.as_mut().unwrap().vote_configuration;
+        vote_configuration.voter_staking_limits = StakingLimits {
+            balance_share: 5000,
+            total_stake_share: Some(4000),
+        };
+        vote_configuration.reputation_quorum = 50;
+        let stake = |voting: &mut Voting, voter: AccountHash, balance: u64, stake: u64| {
+            voting.cast_vote(
+                voter,
+                10,
+                U256::from(balance),
+                U256::from(stake),
+                U256::from(0),
+                VoteDirection::For,
+            )
+        };
+        // Half of the balance at most
+        assert_eq!(
+            stake(&mut voting, ALI, 100, 51),
+            Err(VotingEngineError::StakingLimitReached)
+        );
+        // 40% of the 150 reputation quorum until the vote's stake exceeds it
+        assert_eq!(
+            stake(&mut voting, JON, 200, 61),
+            Err(VotingEngineError::TotalStakeLimitReached)
+        );
+        assert_eq!(stake(&mut voting, JON, 200, 60), Ok(U256::from(60)));
+        assert_eq!(stake(&mut voting, ALI, 100, 50), Ok(U256::from(50)));
+        assert_eq!(stake(&mut voting, BOB, 100, 50), Ok(U256::from(50)));
+    }
+
+    #[test]
+    fn test_total_stake_share_without_a_reputation_quorum() {
+        let mut voting = grant_vote(CountingRule::SimpleMajority, None);
+        voting
+            .proposal
+            .as_mut()
+            .unwrap()
+            .vote_configuration
+            .voter_staking_limits = StakingLimits {
+            balance_share: 10000,
+            total_stake_share: Some(5000),
+        };
+        // The first stake sets the scale
+        cast(&mut voting, ALI, VoteDirection::For, 20);
+        // Half of the 40 the vote would then hold
+        assert_eq!(
+            voting.cast_vote(
+                BOB,
+                10,
+                U256::from(100),
+                U256::from(21),
+                U256::from(0),
+                VoteDirection::Against,
+            ),
+            Err(VotingEngineError::TotalStakeLimitReached)
+        );
+        cast(&mut voting, BOB, VoteDirection::Against, 20);
+    }
+
+    #[test]
+    fn test_voting_power_is_the_uncommitted_snapshot_balance() {
+        let mut voting = grant_vote(CountingRule::SimpleMajority, None);
+        let stake = |voting: &mut Voting, balance: u64, stake: u64, committed: u64| {
+            voting.cast_vote(
+                BOB,
+                10,
+                U256::from(balance),
+                U256::from(stake),
+                U256::from(committed),
+                VoteDirection::For,
+            )
+        };
+        assert_eq!(
+            stake(&mut voting, 0, 0, 0),
+            Err(VotingEngineError::NoReputationAtSnapshot)
+        );
+        assert_eq!(
+            stake(&mut voting, 100, 31, 70),
+            Err(VotingEngineError::InvalidReputationToStake)
+        );
+        // Locked on other votes beyond what was held at the snapshot
+        assert_eq!(
+            stake(&mut voting, 100, 1, 120),
+            Err(VotingEngineError::InvalidReputationToStake)
+        );
+        assert_eq!(stake(&mut voting, 100, 30, 70), Ok(U256::from(30)));
+    }
+
+    #[test]
+    fn test_quorums_are_rounded_up_percentages_of_the_snapshot() {
+        let quorum_vote = |member_quorum: u64, reputation_quorum: u64| {
+            let mut voting = grant_vote(CountingRule::SimpleMajority, None);
+            let vote_configuration: &mut VoteConfiguration =
+                &mut voting.proposal.as_mut().unwrap().vote_configuration;
+            vote_configuration.member_quorum = member_quorum;
+            vote_configuration.reputation_quorum = reputation_quorum;
+            voting
+        };
+        // 34% of three members is 1.02, so two ballots
+        let mut voting = quorum_vote(34, 0);
+        cast(&mut voting, ALI, VoteDirection::For, 100);
+        assert_eq!(
+            voting.clone().calculate_vote_outcome(END + 1, 0),
+            Ok(VoteResult::MemberQuorumUnmet)
+        );
+        cast(&mut voting, BOB, VoteDirection::For, 1);
+        assert_eq!(
+            voting.calculate_vote_outcome(END + 1, 0),
+            Ok(VoteResult::Approved)
+        );
+
+        // 34% of 300 reputation is 102
+        let mut voting = quorum_vote(0, 34);
+        cast(&mut voting, ALI, VoteDirection::For, 100);
+        cast(&mut voting, BOB, VoteDirection::For, 1);
+        assert_eq!(
+            voting.clone().calculate_vote_outcome(END + 1, 0),
+            Ok(VoteResult::ReputationQuorumUnmet)
+        );
+        cast(&mut voting, JON, VoteDirection::Against, 1);
+        assert_eq!(
+            voting.calculate_vote_outcome(END + 1, 0),
+            Ok(VoteResult::Approved)
+        );
+    }
+
+    #[test]
+    fn test_unmet_quorum_is_final_and_reported() {
+        let mut voting = grant_vote(CountingRule::SimpleMajority, None);
+        voting
+            .proposal
+            .as_mut()
+            .unwrap()
+            .vote_configuration
+            .member_quorum = 100;
+        cast(&mut voting, ALI, VoteDirection::For, 30);
+        cast(&mut voting, BOB, VoteDirection::Against, 10);
+        assert_eq!(
+            voting.calculate_vote_outcome(END + 1, 0),
+            Ok(VoteResult::MemberQuorumUnmet)
+        );
+        let outcome_report: VoteOutcomeReport = voting.outcome_report.clone().unwrap();
+        assert_eq!(outcome_report.result, VoteResult::MemberQuorumUnmet);
+        assert!(!outcome_report.member_quorum_met);
+        assert!(outcome_report.reputation_quorum_met);
+        // The tally alone would have approved the grant
+        assert!(outcome_report.threshold_met);
+        assert_eq!(outcome_report.total_members, 2);
+        assert_eq!(outcome_report.eligible_members, 3);
+        assert_eq!(outcome_report.for_votes, U256::from(30));
+        assert_eq!(outcome_report.against_votes, U256::from(10));
+        assert_eq!(outcome_report.member_participation, 66);
+        assert_eq!(outcome_report.reputation_participation, 13);
+        assert_eq!(outcome_report.for_percentage, 75);
+        assert_eq!(
+            voting.calculate_vote_outcome(END + 2, 0),
+            Err(VotingEngineError::VotingNotOngoing)
+        );
+    }
+
+    #[test]
+    fn test_claim_all_skips_what_cannot_be_claimed() {
+        let mut voting = grant_vote(CountingRule::SimpleMajority, None);
+        cast(&mut voting, ALI, VoteDirection::For, 30);
+        cast(&mut voting, BOB, VoteDirection::Against, 10);
+        assert_eq!(
+            voting.claim_all(ALI),
+            (U256::from(0), U256::from(0), U256::from(0))
+        );
+        voting.calculate_vote_outcome(END + 1, 0).unwrap();
+        assert_eq!(
+            voting.claim_all(ALI),
+            (U256::from(10), U256::from(0), U256::from(0))
+        );
+        assert_eq!(
+            voting.claim_all(ALI),
+            (U256::from(0), U256::from(0), U256::from(0))
+        );
+        assert_eq!(
+            voting.claim_all(JON),
+            (U256::from(0), U256::from(0), U256::from(0))
+        );
+
+        let mut voting = grant_vote(CountingRule::SimpleMajority, None);
+        voting
+            .proposal
+            .as_mut()
+            .unwrap()
+            .vote_configuration
+            .member_quorum = 100;
+        voting.proposal.as_mut().unwrap().staked_rep = U256::from(5);
+        cast(&mut voting, ALI, VoteDirection::For, 30);
+        voting.calculate_vote_outcome(END + 1, 0).unwrap();
+        assert_eq!(
+            voting.claim_all(ALI),
+            (U256::from(0), U256::from(30), U256::from(5))
+        );
+    }
+
+    #[test]
+    fn test_settle_page_pays_each_voter_once() {
+        let mut voting = grant_vote(CountingRule::SimpleMajority, None);
+        cast(&mut voting, ALI, VoteDirection::For, 30);
+        cast(&mut voting, BOB, VoteDirection::For, 10);
+        cast(&mut voting, JON, VoteDirection::Against, 20);
+        assert_eq!(voting.settle_page(2), Err(VotingEngineError::VotingOngoing));
+        voting.calculate_vote_outcome(END + 1, 0).unwrap();
+        // A pull claim takes BOB out of the pages
+        assert_eq!(voting.claim_reputation(BOB), Ok(U256::from(5)));
+        assert_eq!(
+            voting.settle_page(1),
+            Ok((alloc::vec![(ALI, U256::from(15))], false))
+        );
+        assert_eq!(
+            voting.settle_page(1),
+            Ok((alloc::vec![(JON, U256::from(0))], true))
+        );
+        assert_eq!(voting.settle_page(1), Ok((Vec::new(), true)));
+        assert_eq!(
+            voting.claim_reputation(ALI),
+            Err(VotingEngineError::ReputationAlreadyClaimed)
+        );
+        assert_eq!(voting.take_rounding_dust(), U256::from(0));
+    }
+
+    #[test]
+    fn test_settle_page_refunds_the_proposer_of_a_failed_vote() {
+        let mut voting = grant_vote(CountingRule::SimpleMajority, None);
+        voting
+            .proposal
+            .as_mut()
+            .unwrap()
+            .vote_configuration
+            .reputation_quorum = 50;
+        voting.proposal.as_mut().unwrap().staked_rep = U256::from(5);
+        cast(&mut voting, BOB, VoteDirection::For, 10);
+        voting.calculate_vote_outcome(END + 1, 0).unwrap();
+        // BOB's stake was only locked, the proposer stake is held by the voting engine
+        assert_eq!(
+            voting.settle_page(5),
+            Ok((
+                alloc::vec![(BOB, U256::from(0)), (ALI, U256::from(5))],
+                true
+            ))
+        );
+        assert_eq!(
+            voting.get_proposer_stake(ALI),
+            Err(VotingEngineError::ReputationAlreadyClaimed)
+        );
+    }
+
+    // ALI proposed a grant worth 10000 input reputation, half of it for the voters who back it
+    fn approved_grant(against_stake: u64, uncollected_stake: u64) -> Voting {
+        let mut voting = grant_vote(CountingRule::SimpleMajority, None);
+        voting.proposal.as_mut().unwrap().cost = U256::from(1);
+        cast(&mut voting, ALI, VoteDirection::For, 10);
+        cast(&mut voting, BOB, VoteDirection::For, 20);
+        cast(&mut voting, JON, VoteDirection::Against, against_stake);
+        voting.calculate_vote_outcome(END + 1, 1).unwrap();
+        voting.record_uncollected_stake(U256::from(uncollected_stake));
+        voting
+    }
+
+    #[test]
+    fn test_rewards_are_rounded_down_and_the_dust_collected_once() {
+        let mut voting = approved_grant(1, 0);
+        assert_eq!(voting.input_reputation, U256::from(10000));
+        // A third of the losing stake rounds down to nothing, 1666 of the policing share and the
+        // other half as OP
+        assert_eq!(voting.claim_reputation(ALI), Ok(U256::from(6666)));
+        assert_eq!(voting.take_rounding_dust(), U256::from(0));
+        assert_eq!(voting.claim_reputation(BOB), Ok(U256::from(3333)));
+        assert_eq!(voting.claim_reputation(JON), Ok(U256::from(0)));
+        assert_eq!(voting.take_rounding_dust(), U256::from(2));
+        assert_eq!(voting.take_rounding_dust(), U256::from(0));
+    }
+
+    #[test]
+    fn test_uncollected_stakes_are_not_paid_out() {
+        // 3 of JON's 9 could not be debited, so only 6 are shared
+        let mut voting = approved_grant(9, 3);
+        assert_eq!(voting.claim_reputation(ALI), Ok(U256::from(6668)));
+        assert_eq!(voting.claim_reputation(BOB), Ok(U256::from(3337)));
+        assert_eq!(voting.take_rounding_dust(), U256::from(1));
+    }
+
+    #[test]
+    fn test_grant_votes_have_nothing_to_execute() {
+        let mut voting = grant_vote(CountingRule::SimpleMajority, None);
+        cast(&mut voting, ALI, VoteDirection::For, 10);
+        voting.calculate_vote_outcome(END + 1, 0).unwrap();
+        assert_eq!(voting.governance_execution, None);
+        assert_eq!(
+            voting.execute_governance_actions(END + 1),
+            Err(VotingEngineError::ExecutionNotQueued)
+        );
+        assert_eq!(
+            voting.veto_governance_actions(BOB, END + 1),
+            Err(VotingEngineError::ExecutionNotQueued)
+        );
+    }
+
+    // Governance vote with its transition vote ending at 50 and a full vote lasting 50 once the
+    // transition passes, quorums and thresholds are given as (quorum, threshold)
+    fn governance_vote(transition_vote: (u64, u64), full_vote: (u64, u64)) -> Voting {
+        let governance_proposal: GovernanceProposal = GovernanceProposal::new(
+            "governance".to_string(),
+            (
+                (
+                    transition_vote.0,
+                    transition_vote.1,
+                    "repository".to_string(),
+                ),
+                (full_vote.0, full_vote.1, 50),
+                (
+                    50,
+                    None,
+                    (CountingRule::SimpleMajority as u8, (10000, None)),
+                ),
+            ),
+            U256::from(0),
+            ALI,
+            U256::from(100),
+            Vec::new(),
+            "url".to_string(),
+            alloc::vec![GovernanceAction::PolicingRatio(30).serialize()],
+            Some(VotePolicy {
+                min_member_quorum: 0,
+                min_reputation_quorum: 0,
+                min_threshold: 0,
+                min_duration: 0,
+                max_duration: 100,
+                min_balance_share: 0,
+                max_balance_share: 10000,
+                min_total_stake_share: 0,
+                max_total_stake_share: 10000,
+                counting_rules: 1 << CountingRule::SimpleMajority as u8,
+                allow_secret_ballot: false,
+                min_reveal_timeout: 0,
+            }),
+            0,
+        )
+        .unwrap();
+        let late_swing_rule: LateSwingRule = LateSwingRule {
+            window: 0,
+            extension: 0,
+            max_extensions: 0,
+        };
+        Voting::new_governance(
+            0,
+            governance_proposal.serialize(),
+            late_swing_rule,
+            3,
+            U256::from(300),
+            20,
+        )
+        .unwrap()
+    }
+
+    fn stake_full_vote(
+        voting: &mut Voting,
+        voter: AccountHash,
+        direction: VoteDirection,
+        stake: u64,
+    ) {
+        voting
+            .cast_vote(
+                voter,
+                60,
+                U256::from(100),
+                U256::from(stake),
+                U256::from(0),
+                direction,
+            )
+            .unwrap();
+    }
+
+    #[test]
+    fn test_transition_vote_opens_the_full_vote() {
+        let mut voting = governance_vote((10, 50), (10, 50));
+        assert_eq!(voting.end_at(), 50);
+        // Transition ballots are weighted by the balance and stake nothing
+        assert_eq!(
+            voting.cast_vote(
+                BOB,
+                10,
+                U256::from(100),
+                U256::from(40),
+                U256::from(0),
+                VoteDirection::For
+            ),
+            Ok(U256::from(0))
+        );
+        assert_eq!(voting.transition_for_votes, U256::from(100));
+        assert_eq!(
+            voting.cast_vote(
+                BOB,
+                20,
+                U256::from(100),
+                U256::from(0),
+                U256::from(0),
+                VoteDirection::Against
+            ),
+            Err(VotingEngineError::AlreadyVoted)
+        );
+        assert_eq!(
+            voting.calculate_governance_vote_outcome(49),
+            Err(VotingEngineError::VotingOngoing)
+        );
+        assert_eq!(
+            voting.cast_vote(
+                JON,
+                51,
+                U256::from(100),
+                U256::from(0),
+                U256::from(0),
+                VoteDirection::For
+            ),
+            Err(VotingEngineError::VotingEnded)
+        );
+        assert_eq!(
+            voting.calculate_governance_vote_outcome(51),
+            Ok(VoteResult::InVote)
+        );
+        // The full vote lasts its own timeout from the transition
+        assert_eq!(voting.full_vote_start_timestamp, 51);
+        assert_eq!(voting.end_at(), 101);
+        // Transition voters vote again, staking this time
+        stake_full_vote(&mut voting, BOB, VoteDirection::For, 40);
+        assert_eq!(voting.total_staked_reputation, U256::from(40));
+        assert_eq!(
+            voting.calculate_governance_vote_outcome(102),
+            Ok(VoteResult::Approved)
+        );
+    }
+
+    #[test]
+    fn test_transition_vote_failures_are_final() {
+        // 50% of the 300 reputation
+        let mut voting = governance_vote((50, 50), (10, 50));
+        cast(&mut voting, BOB, VoteDirection::For, 0);
+        assert_eq!(
+            voting.calculate_governance_vote_outcome(51),
+            Ok(VoteResult::ReputationQuorumUnmet)
+        );
+        assert_eq!(
+            voting.governance_proposal.clone().unwrap().proposal_status,
+            ProposalStatus::FullVoteComplete
+        );
+        assert_eq!(
+            voting.cast_vote(
+                JON,
+                60,
+                U256::from(100),
+                U256::from(10),
+                U256::from(0),
+                VoteDirection::For
+            ),
+            Err(VotingEngineError::VotingEnded)
+        );
+
+        let mut voting = governance_vote((10, 50), (10, 50));
+        cast(&mut voting, BOB, VoteDirection::For, 0);
+        cast(&mut voting, JON, VoteDirection::Against, 0);
+        cast(&mut voting, ALI, VoteDirection::Against, 0);
+        assert_eq!(
+            voting.calculate_governance_vote_outcome(51),
+            Ok(VoteResult::Rejected)
+        );
+        assert_eq!(voting.end_at(), 50);
+        assert_eq!(
+            voting.calculate_governance_vote_outcome(52),
+            Err(VotingEngineError::VotingNotOngoing)
+        );
+    }
+
+    #[test]
+    fn test_full_vote_failures_after_the_transition() {
+        // 50% of the 300 reputation must be staked in the full vote
+        let mut voting = governance_vote((10, 50), (50, 50));
+        cast(&mut voting, BOB, VoteDirection::For, 0);
+        voting.calculate_governance_vote_outcome(51).unwrap();
+        stake_full_vote(&mut voting, BOB, VoteDirection::For, 100);
+        assert_eq!(
+            voting.calculate_governance_vote_outcome(102),
+            Ok(VoteResult::ReputationQuorumUnmet)
+        );
+        assert_eq!(
+            voting.governance_execution.unwrap().status,
+            ExecutionStatus::AwaitingApproval
+        );
+
+        let mut voting = governance_vote((10, 50), (10, 50));
+        cast(&mut voting, BOB, VoteDirection::For, 0);
+        voting.calculate_governance_vote_outcome(51).unwrap();
+        stake_full_vote(&mut voting, BOB, VoteDirection::For, 40);
+        stake_full_vote(&mut voting, JON, VoteDirection::Against, 60);
+        assert_eq!(
+            voting.calculate_governance_vote_outcome(102),
+            Ok(VoteResult::Rejected)
+        );
+        assert_eq!(voting.claim_reputation(JON), Ok(U256::from(40)));
+    }
+
+    #[test]
+    fn test_approved_governance_actions_are_queued_behind_the_timelock() {
+        let mut voting = governance_vote((10, 50), (10, 50));
+        // The transition vote ends at 50 and opens the full vote until 101
+        cast(&mut voting, BOB, VoteDirection::For, 0);
+        assert_eq!(
+            voting.calculate_governance_vote_outcome(51),
+            Ok(VoteResult::InVote)
+        );
+        voting
+            .cast_vote(
+                BOB,
+                60,
+                U256::from(100),
+                U256::from(40),
+                U256::from(0),
+                VoteDirection::For,
+            )
+            .unwrap();
+        assert_eq!(
+            voting.calculate_governance_vote_outcome(102),
+            Ok(VoteResult::Approved)
+        );
+        assert_eq!(
+            voting.outcome_report.clone().unwrap().action_results,
+            alloc::vec![GovernanceActionResult::Pending]
+        );
+        assert_eq!(
+            voting.execute_governance_actions(121),
+            Err(VotingEngineError::TimelockNotExpired)
+        );
+        assert_eq!(
+            voting.execute_governance_actions(122),
+            Ok(alloc::vec![GovernanceAction::PolicingRatio(30)])
+        );
+        assert_eq!(
+            voting.execute_governance_actions(123),
+            Err(VotingEngineError::ExecutionNotQueued)
+        );
+    }
+}